@@ -0,0 +1,76 @@
+use crate::models::stats::Stats;
+use crate::simulation::{Simulation, SimulationConfig};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Plays every game in `configs` to the end across the threads of the current Rayon thread pool and
+/// returns the final stats of each, in the same order as `configs`. A game that can not be played,
+/// such as one with more players than fit on its field, gets the error from [Simulation::new].
+///
+/// Every game is built and played on a single thread with its own random number generator, so the
+/// results only depend on the configs and never on how many threads there are. For that reason every
+/// config must have a seed. To limit the number of threads run the batch inside
+/// [ThreadPool::install](rayon::ThreadPool::install).
+pub fn run_batch(configs: &[SimulationConfig]) -> Vec<Result<Stats, String>> {
+    run_batch_with_progress(configs, |_, _| ())
+}
+
+/// The same as [run_batch], calling `progress` after each game with the number of games played so
+/// far and the total. Games finish in any order and `progress` is called from the thread that
+/// played the game.
+pub fn run_batch_with_progress<F>(configs: &[SimulationConfig], progress: F) -> Vec<Result<Stats, String>>
+where
+    F: Fn(usize, usize) + Sync
+{
+    let played = AtomicUsize::new(0);
+    configs
+        .par_iter()
+        .map(|config| {
+            let result = play(config);
+            progress(played.fetch_add(1, Ordering::Relaxed) + 1, configs.len());
+            result
+        })
+        .collect()
+}
+
+fn play(config: &SimulationConfig) -> Result<Stats, String> {
+    if config.seed.is_none() {
+        return Err("every game in a batch needs a seed".to_owned());
+    }
+
+    Ok(Simulation::new(config.clone())?.run())
+}
+
+#[test]
+fn run_batch_test() {
+    let configs: Vec<SimulationConfig> = (0..8)
+        .map(|seed| SimulationConfig {
+            num_players: 4,
+            x_axis_len: 6,
+            y_axis_len: 6,
+            num_turns: 40,
+            seed: Some(seed),
+            ..SimulationConfig::default()
+        })
+        .chain(vec![SimulationConfig::default()])
+        .collect();
+    let run_on = |threads| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+            .install(|| run_batch(&configs))
+    };
+
+    let one_thread = run_on(1);
+    assert_eq!(one_thread.len(), 9);
+    assert_eq!(
+        one_thread[0].as_ref().unwrap(),
+        &Simulation::new(configs[0].clone()).unwrap().run()
+    );
+    assert_eq!(
+        one_thread[8].as_ref().unwrap_err(),
+        "every game in a batch needs a seed"
+    );
+    assert_eq!(run_on(4), one_thread);
+}
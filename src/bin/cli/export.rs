@@ -0,0 +1,128 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{channel, Receiver};
+use wopr_tag::image_renderer::{GifRecorder, ImageRenderer};
+use wopr_tag::{Event, Simulation};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrameFormat {
+    Svg,
+    Png
+}
+
+impl FromStr for FrameFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "svg" => Ok(FrameFormat::Svg),
+            "png" => Ok(FrameFormat::Png),
+            _ => Err(format!("unknown frame format: {}. Must be one of: svg, png", name))
+        }
+    }
+}
+
+/// Saves every turn of a simulation as an image and/or a frame of an animated GIF. Players tagged
+/// on a turn are highlighted in its image.
+pub struct FrameExporter {
+    renderer: ImageRenderer,
+    frames: Option<(PathBuf, FrameFormat)>,
+    gif: Option<GifRecorder<BufWriter<File>>>,
+    events: Receiver<Event>
+}
+
+impl FrameExporter {
+    /// Starts exporting `simulation`, which must not have played any turns yet, and saves the
+    /// starting positions as turn 0.
+    pub fn new(
+        simulation: &mut Simulation,
+        renderer: ImageRenderer,
+        frames: Option<(PathBuf, FrameFormat)>,
+        gif: Option<(PathBuf, u64)>
+    ) -> Result<Self, String> {
+        if let Some((frames_dir, _)) = &frames {
+            fs::create_dir_all(frames_dir)
+                .map_err(|err| format!("could not create {}: {}", frames_dir.display(), err))?;
+        }
+        let gif = match gif {
+            Some((gif_path, frame_delay_ms)) => {
+                let file = File::create(&gif_path)
+                    .map_err(|err| format!("could not create {}: {}", gif_path.display(), err))?;
+                Some(GifRecorder::new(
+                    BufWriter::new(file),
+                    renderer,
+                    simulation.field_of_play(),
+                    frame_delay_ms
+                )?)
+            }
+            None => None
+        };
+        let (sender, events) = channel();
+        simulation.add_event_sink(Box::new(sender));
+
+        let mut exporter = FrameExporter {
+            renderer,
+            frames,
+            gif,
+            events
+        };
+        exporter.export_turn(simulation)?;
+
+        Ok(exporter)
+    }
+
+    /// Saves the turn that was just played.
+    pub fn export_turn(&mut self, simulation: &Simulation) -> Result<(), String> {
+        let tagged_players: Vec<usize> = self
+            .events
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::Tagged { tagged_player, .. } => Some(tagged_player),
+                _ => None
+            })
+            .collect();
+        let field_of_play = simulation.field_of_play();
+        let players = simulation.players();
+
+        if let Some((frames_dir, format)) = &self.frames {
+            let extension = match format {
+                FrameFormat::Svg => "svg",
+                FrameFormat::Png => "png"
+            };
+            let path = frames_dir.join(format!("turn-{:06}.{}", simulation.turn_num(), extension));
+            let write_err = |err: String| format!("could not write {}: {}", path.display(), err);
+            match format {
+                FrameFormat::Svg => {
+                    let svg = self
+                        .renderer
+                        .render_svg(field_of_play, players, simulation.turn_num(), &tagged_players);
+                    fs::write(&path, svg).map_err(|err| write_err(err.to_string()))?;
+                }
+                FrameFormat::Png => {
+                    let file = File::create(&path).map_err(|err| write_err(err.to_string()))?;
+                    self.renderer
+                        .write_png(BufWriter::new(file), field_of_play, players, &tagged_players)
+                        .map_err(write_err)?;
+                }
+            }
+        }
+        if let Some(gif) = &mut self.gif {
+            gif.add_frame(field_of_play, players, &tagged_players)?;
+        }
+
+        Ok(())
+    }
+
+    /// Finishes the GIF, if there is one.
+    pub fn finish(self) -> Result<(), String> {
+        if let Some(gif) = self.gif {
+            gif.finish()?
+                .flush()
+                .map_err(|err| format!("could not write the GIF: {}", err))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,567 @@
+use crossterm::event::{self, Event as TerminalEvent, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+use wopr_tag::{FieldOfPlay, Player, Replay, Simulation};
+
+// The side panel is drawn to the right of the field and is this many columns wide.
+const PANEL_WIDTH: u16 = 34;
+const RECENT_TAG_COUNT: usize = 8;
+const MIN_WAIT_MS: u64 = 10;
+const MAX_WAIT_MS: u64 = 5000;
+// The most turns kept to go back to, and the most player positions kept over all of them. The latest
+// turn is always kept.
+const MAX_HISTORY_TURNS: usize = 100;
+const HISTORY_POSITIONS: usize = 1_000_000;
+
+/// A game the viewer can step through, either being played live or replayed from an event log.
+pub trait Game {
+    /// Plays or replays the next turn. Returns false once there are no more turns.
+    fn next_turn(&mut self) -> Result<bool, String>;
+    fn field_of_play(&self) -> &FieldOfPlay;
+    fn players(&self) -> &[Player];
+    fn turn_num(&self) -> usize;
+    /// Extra lines for the side panel.
+    fn stats_lines(&self) -> Vec<String>;
+}
+
+impl Game for Simulation {
+    fn next_turn(&mut self) -> Result<bool, String> {
+        Ok(self.step())
+    }
+
+    fn field_of_play(&self) -> &FieldOfPlay {
+        Simulation::field_of_play(self)
+    }
+
+    fn players(&self) -> &[Player] {
+        Simulation::players(self)
+    }
+
+    fn turn_num(&self) -> usize {
+        Simulation::turn_num(self)
+    }
+
+    fn stats_lines(&self) -> Vec<String> {
+        let stats = self.stats();
+        vec![
+            format!("mode: {}", self.game_mode()),
+            format!("seed: {}", self.seed()),
+            format!("tags: {}", stats.tag_count()),
+            format!("tags per 100 turns: {:.2}", stats.tags_per_turn() * 100.0),
+            format!(
+                "mean turns between tags: {}",
+                stats
+                    .mean_turns_between_tags()
+                    .map_or_else(|| "n/a".to_owned(), |mean| format!("{:.2}", mean))
+            ),
+        ]
+    }
+}
+
+impl Game for Replay {
+    fn next_turn(&mut self) -> Result<bool, String> {
+        self.step()
+    }
+
+    fn field_of_play(&self) -> &FieldOfPlay {
+        Replay::field_of_play(self)
+    }
+
+    fn players(&self) -> &[Player] {
+        Replay::players(self)
+    }
+
+    fn turn_num(&self) -> usize {
+        Replay::turn_num(self)
+    }
+
+    fn stats_lines(&self) -> Vec<String> {
+        vec![
+            format!("mode: {}", self.game_mode()),
+            "replaying an event log".to_owned(),
+        ]
+    }
+}
+
+// The state of a player at the end of a turn: where they are, whether they are it and whether they
+// are frozen.
+type PlayerState = (usize, usize, bool, bool);
+
+// The players at the end of a turn, and what the viewer worked out had happened in it.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Frame {
+    turn: usize,
+    players: Vec<PlayerState>,
+    stuck_players: HashSet<usize>,
+    last_it_player: Option<usize>
+}
+
+impl Frame {
+    fn first(turn: usize, players: &[Player]) -> Self {
+        Frame {
+            turn,
+            players: snapshot(players),
+            ..Frame::default()
+        }
+    }
+
+    // The frame for the next turn, worked out by comparing `players` with how they were in this
+    // frame, along with a line for every player who became it or was frozen.
+    fn next(&self, turn: usize, players: Vec<PlayerState>) -> (Frame, Vec<String>) {
+        let mut stuck_players = HashSet::new();
+        let mut last_it_player = self.last_it_player;
+        let mut tags = Vec::new();
+        players.iter().zip(self.players.iter()).enumerate().for_each(
+            |(index, (&(x, y, is_it, is_frozen), &(old_x, old_y, was_it, was_frozen)))| {
+                if (x, y) == (old_x, old_y) && !is_frozen {
+                    stuck_players.insert(index);
+                }
+                if was_it && !is_it {
+                    last_it_player = Some(index);
+                }
+                if is_it && !was_it {
+                    tags.push(format!("turn {}: p{} became it", turn, index));
+                } else if is_frozen && !was_frozen {
+                    tags.push(format!("turn {}: p{} was frozen", turn, index));
+                }
+            }
+        );
+        let frame = Frame {
+            turn,
+            players,
+            stuck_players,
+            last_it_player
+        };
+
+        (frame, tags)
+    }
+}
+
+// Which turn the viewer is showing and how it moves on. The last few turns are kept so the viewer
+// can go back and look at them again. Knows nothing about the terminal so it can be tested.
+struct Playback {
+    wait: Duration,
+    is_paused: bool,
+    is_finished: bool,
+    // Oldest first. Never empty.
+    frames: VecDeque<Frame>,
+    max_frames: usize,
+    // How many turns before the latest turn the viewer is showing. 0 shows the latest turn.
+    rewound_by: usize
+}
+
+impl Playback {
+    fn new(wait_between_turn_ms: u64, first: Frame) -> Self {
+        // Keep fewer turns of big games so rewinding does not use up the memory.
+        let max_frames = (HISTORY_POSITIONS / first.players.len().max(1)).clamp(1, MAX_HISTORY_TURNS);
+        Playback {
+            wait: Duration::from_millis(wait_between_turn_ms.clamp(MIN_WAIT_MS, MAX_WAIT_MS)),
+            is_paused: false,
+            is_finished: false,
+            frames: vec![first].into(),
+            max_frames,
+            rewound_by: 0
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        self.is_paused = !self.is_paused;
+    }
+
+    fn speed_up(&mut self) {
+        self.wait = (self.wait / 2).max(Duration::from_millis(MIN_WAIT_MS));
+    }
+
+    fn slow_down(&mut self) {
+        self.wait = (self.wait * 2).min(Duration::from_millis(MAX_WAIT_MS));
+    }
+
+    // Goes back a turn and pauses there. Stays on the oldest turn kept once it gets there.
+    fn rewind(&mut self) {
+        self.is_paused = true;
+        if self.rewound_by + 1 < self.frames.len() {
+            self.rewound_by += 1;
+        }
+    }
+
+    // Moves on a turn. Returns true if a new turn must be played, and false if the viewer moved on
+    // to a turn it had already played or the game is over.
+    fn step(&mut self) -> bool {
+        if self.rewound_by > 0 {
+            self.rewound_by -= 1;
+            return false;
+        }
+
+        !self.is_finished
+    }
+
+    // Adds the frame of a turn that was just played.
+    fn push(&mut self, frame: Frame) {
+        self.frames.push_back(frame);
+        if self.frames.len() > self.max_frames {
+            self.frames.pop_front();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.is_finished = true;
+    }
+
+    // Whether turns move on without a key being pressed.
+    fn is_playing(&self) -> bool {
+        !self.is_paused && (!self.is_finished || self.rewound_by > 0)
+    }
+
+    fn latest(&self) -> &Frame {
+        self.frames.back().expect("There is always a frame.")
+    }
+
+    fn shown(&self) -> &Frame {
+        &self.frames[self.frames.len() - 1 - self.rewound_by]
+    }
+
+    fn state(&self) -> &'static str {
+        if self.rewound_by > 0 {
+            "rewound"
+        } else if self.is_finished {
+            "finished"
+        } else if self.is_paused {
+            "paused"
+        } else {
+            "playing"
+        }
+    }
+}
+
+// What the viewer remembers between turns.
+struct Viewer {
+    playback: Playback,
+    // The top left position of the field that is drawn in the top left of the terminal.
+    viewport: (usize, usize),
+    recent_tags: VecDeque<String>,
+    error: Option<String>
+}
+
+/// Takes over the terminal and plays the game until it ends and the user quits. Keys:
+/// space pauses and resumes, n plays a single turn while paused, b goes back a turn, + and - change
+/// the speed, the arrow keys scroll the field and q quits. Returns the error that stopped the game
+/// early, if there was one.
+pub fn run<G: Game>(game: &mut G, wait_between_turn_ms: u64) -> io::Result<Option<String>> {
+    // Log lines would be written over the viewer, so logging is turned off until it closes.
+    let max_log_level = log::max_level();
+    log::set_max_level(log::LevelFilter::Off);
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = view(game, wait_between_turn_ms, &mut stdout);
+    execute!(stdout, ResetColor, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    log::set_max_level(max_log_level);
+
+    result
+}
+
+fn view<G: Game>(game: &mut G, wait_between_turn_ms: u64, stdout: &mut Stdout) -> io::Result<Option<String>> {
+    let mut viewer = Viewer {
+        playback: Playback::new(wait_between_turn_ms, Frame::first(game.turn_num(), game.players())),
+        viewport: (0, 0),
+        recent_tags: VecDeque::new(),
+        error: None
+    };
+    let mut next_turn_at = Instant::now();
+
+    loop {
+        // Storing where the field was drawn from means scrolling back works straight away after
+        // scrolling too far.
+        viewer.viewport = draw(game, &viewer, stdout)?;
+        let timeout = if viewer.playback.is_playing() {
+            next_turn_at.saturating_duration_since(Instant::now())
+        } else {
+            Duration::from_millis(250)
+        };
+        if event::poll(timeout)? {
+            if let TerminalEvent::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                let playback = &mut viewer.playback;
+                match code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(viewer.error),
+                    KeyCode::Char(' ') | KeyCode::Char('p') => playback.toggle_pause(),
+                    // Stepping through turns already played does not play a new one.
+                    KeyCode::Char('n') | KeyCode::Char('.') if playback.is_paused && playback.step() => {
+                        play_turn(game, &mut viewer)
+                    }
+                    KeyCode::Char('b') | KeyCode::Char(',') => playback.rewind(),
+                    KeyCode::Char('+') | KeyCode::Char('=') => playback.speed_up(),
+                    KeyCode::Char('-') | KeyCode::Char('_') => playback.slow_down(),
+                    KeyCode::Left | KeyCode::Char('h') => viewer.viewport.0 = viewer.viewport.0.saturating_sub(1),
+                    KeyCode::Right | KeyCode::Char('l') => viewer.viewport.0 += 1,
+                    KeyCode::Up | KeyCode::Char('k') => viewer.viewport.1 = viewer.viewport.1.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => viewer.viewport.1 += 1,
+                    _ => {}
+                }
+            }
+        } else if viewer.playback.is_playing() {
+            if viewer.playback.step() {
+                play_turn(game, &mut viewer);
+            }
+            next_turn_at = Instant::now() + viewer.playback.wait;
+        }
+    }
+}
+
+fn play_turn<G: Game>(game: &mut G, viewer: &mut Viewer) {
+    match game.next_turn() {
+        Ok(true) => {}
+        Ok(false) => {
+            viewer.playback.finish();
+            return;
+        }
+        Err(err) => {
+            viewer.error = Some(err);
+            viewer.playback.finish();
+            return;
+        }
+    }
+
+    let (frame, tags) = viewer.playback.latest().next(game.turn_num(), snapshot(game.players()));
+    for tag in tags {
+        viewer.recent_tags.push_front(tag);
+    }
+    viewer.recent_tags.truncate(RECENT_TAG_COUNT);
+    viewer.playback.push(frame);
+}
+
+fn snapshot(players: &[Player]) -> Vec<PlayerState> {
+    players
+        .iter()
+        .map(|player| {
+            let (x, y) = player.get_location();
+            (x, y, player.is_it, player.is_frozen)
+        })
+        .collect()
+}
+
+// Returns the top left position of the field that was drawn.
+fn draw<G: Game>(game: &G, viewer: &Viewer, stdout: &mut Stdout) -> io::Result<(usize, usize)> {
+    let (columns, rows) = terminal::size()?;
+    let field_of_play = game.field_of_play();
+    let y_len = field_of_play.y_len();
+    let x_len = field_of_play.x_len();
+    let visible_columns = (columns.saturating_sub(PANEL_WIDTH + 1) as usize).min(x_len);
+    let visible_rows = (rows as usize).min(y_len);
+    let (left, top) = clamp_viewport(viewer.viewport, (visible_columns, visible_rows), (x_len, y_len));
+
+    let frame = viewer.playback.shown();
+    let it_count = frame.players.iter().filter(|(_, _, is_it, _)| *is_it).count();
+    let frozen_count = frame.players.iter().filter(|(_, _, _, is_frozen)| *is_frozen).count();
+    let mut panel = vec![
+        format!("turn {} ({})", frame.turn, viewer.playback.state()),
+        format!("wait: {}ms", viewer.playback.wait.as_millis()),
+        format!("field: {} x {}, showing {},{}", x_len, y_len, left, top),
+        format!(
+            "players: {}, it: {}, frozen: {}",
+            frame.players.len(),
+            it_count,
+            frozen_count
+        ),
+    ];
+    panel.extend(game.stats_lines());
+    panel.push(String::new());
+    panel.push("recent tags:".to_owned());
+    panel.extend(viewer.recent_tags.iter().cloned());
+    panel.push(String::new());
+    if let Some(err) = &viewer.error {
+        panel.push(format!("error: {}", err));
+    }
+    panel.push("space pause, n step, b back".to_owned());
+    panel.push("+/- speed".to_owned());
+    panel.push("arrows scroll, q quit".to_owned());
+
+    // The frame shown may be from an earlier turn, so players are drawn where they were then rather
+    // than where the field of play has them now.
+    let occupants: HashMap<(usize, usize), usize> = frame
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(_, (x, y, _, _))| (left..left + visible_columns).contains(x) && (top..top + visible_rows).contains(y))
+        .map(|(index, (x, y, _, _))| ((*x, *y), index))
+        .collect();
+
+    // Every row is drawn over rather than clearing the screen first, which would flicker.
+    for row in 0..rows as usize {
+        queue!(stdout, cursor::MoveTo(0, row as u16))?;
+        if row < visible_rows {
+            // Only change colour when it is different from the last position to keep output small.
+            let mut current_color = None;
+            for x in left..left + visible_columns {
+                let occupant = occupants.get(&(x, top + row)).copied();
+                let (symbol, color) = cell(field_of_play, frame, occupant, x, top + row);
+                if current_color != Some(color) {
+                    queue!(stdout, SetForegroundColor(color))?;
+                    current_color = Some(color);
+                }
+                queue!(stdout, Print(symbol))?;
+            }
+        } else {
+            queue!(stdout, Print(" ".repeat(visible_columns)))?;
+        }
+        let line: String = panel
+            .get(row)
+            .map(|line| line.chars().take(PANEL_WIDTH as usize).collect())
+            .unwrap_or_default();
+        queue!(
+            stdout,
+            SetForegroundColor(Color::Reset),
+            Print(" "),
+            Print(line),
+            terminal::Clear(ClearType::UntilNewLine)
+        )?;
+    }
+
+    stdout.flush()?;
+
+    Ok((left, top))
+}
+
+// Keeps the viewport on the field when the terminal is resized or the user scrolls too far, so the
+// `visible` columns and rows from it are all on the field.
+fn clamp_viewport(
+    (left, top): (usize, usize),
+    (visible_columns, visible_rows): (usize, usize),
+    (x_len, y_len): (usize, usize)
+) -> (usize, usize) {
+    (
+        left.min(x_len.saturating_sub(visible_columns)),
+        top.min(y_len.saturating_sub(visible_rows))
+    )
+}
+
+// The symbol and colour to draw for a position on the field, where `occupant` is the index of the
+// player there in `frame`.
+fn cell(field_of_play: &FieldOfPlay, frame: &Frame, occupant: Option<usize>, x: usize, y: usize) -> (char, Color) {
+    let terrain = field_of_play.get_terrain(x, y);
+    if !terrain.is_passable() {
+        return (terrain.symbol(), Color::DarkGrey);
+    }
+
+    match occupant {
+        Some(index) if frame.players[index].2 => ('*', Color::Red),
+        Some(index) if frame.players[index].3 => ('F', Color::Cyan),
+        Some(index) if frame.last_it_player == Some(index) => ('P', Color::Yellow),
+        Some(index) if frame.stuck_players.contains(&index) => ('P', Color::Magenta),
+        Some(_) => ('P', Color::Green),
+        None => ('-', Color::DarkGrey)
+    }
+}
+
+#[cfg(test)]
+fn frame(turn: usize, num_players: usize) -> Frame {
+    Frame {
+        turn,
+        players: vec![(0, 0, false, false); num_players],
+        ..Frame::default()
+    }
+}
+
+#[test]
+fn clamp_viewport_test() {
+    assert_eq!(clamp_viewport((3, 4), (10, 5), (100, 50)), (3, 4));
+    assert_eq!(clamp_viewport((95, 48), (10, 5), (100, 50)), (90, 45));
+    // A field smaller than the terminal is always drawn from its top left.
+    assert_eq!(clamp_viewport((2, 2), (8, 8), (8, 8)), (0, 0));
+}
+
+#[test]
+fn frame_next_test() {
+    let first = Frame {
+        players: vec![(0, 0, true, false), (2, 2, false, false), (4, 4, false, false)],
+        ..Frame::default()
+    };
+    let (second, tags) = first.next(1, vec![(1, 0, false, false), (2, 2, true, false), (4, 4, false, true)]);
+
+    assert_eq!(second.turn, 1);
+    assert_eq!(second.last_it_player, Some(0));
+    // Frozen players are not stuck, they can not move.
+    assert_eq!(second.stuck_players, vec![1].into_iter().collect());
+    assert_eq!(tags, vec!["turn 1: p1 became it", "turn 1: p2 was frozen"]);
+
+    // The last it player is remembered until someone else stops being it.
+    let (third, tags) = second.next(2, vec![(1, 1, false, false), (2, 3, true, false), (4, 4, false, true)]);
+    assert_eq!(third.last_it_player, Some(0));
+    assert!(third.stuck_players.is_empty());
+    assert!(tags.is_empty());
+}
+
+#[test]
+fn playback_pause_and_speed_test() {
+    let mut playback = Playback::new(250, frame(0, 2));
+    assert!(playback.is_playing());
+    assert_eq!(playback.state(), "playing");
+    playback.toggle_pause();
+    assert!(!playback.is_playing());
+    assert_eq!(playback.state(), "paused");
+    playback.toggle_pause();
+    assert!(playback.is_playing());
+
+    playback.speed_up();
+    assert_eq!(playback.wait, Duration::from_millis(125));
+    (0..10).for_each(|_| playback.speed_up());
+    assert_eq!(playback.wait, Duration::from_millis(MIN_WAIT_MS));
+    (0..20).for_each(|_| playback.slow_down());
+    assert_eq!(playback.wait, Duration::from_millis(MAX_WAIT_MS));
+    assert_eq!(Playback::new(0, frame(0, 2)).wait, Duration::from_millis(MIN_WAIT_MS));
+}
+
+#[test]
+fn playback_rewind_and_step_test() {
+    let mut playback = Playback::new(250, frame(0, 2));
+    // Nothing to go back to yet.
+    playback.rewind();
+    assert_eq!(playback.shown().turn, 0);
+    assert!(playback.is_paused);
+
+    (1..=3).for_each(|turn| playback.push(frame(turn, 2)));
+    playback.rewind();
+    playback.rewind();
+    assert_eq!(playback.shown().turn, 1);
+    assert_eq!(playback.state(), "rewound");
+    // Stepping moves forward through the turns already played before playing new ones.
+    assert!(!playback.step());
+    assert_eq!(playback.shown().turn, 2);
+    assert!(!playback.step());
+    assert_eq!(playback.shown().turn, 3);
+    assert!(playback.step());
+
+    // Once the game is over only the turns already played can be stepped through.
+    playback.rewind();
+    playback.finish();
+    playback.toggle_pause();
+    assert!(playback.is_playing());
+    assert!(!playback.step());
+    assert!(!playback.is_playing());
+    assert!(!playback.step());
+    assert_eq!(playback.state(), "finished");
+}
+
+#[test]
+fn playback_history_test() {
+    let mut playback = Playback::new(250, frame(0, 2));
+    (1..=MAX_HISTORY_TURNS * 2).for_each(|turn| playback.push(frame(turn, 2)));
+    assert_eq!(playback.frames.len(), MAX_HISTORY_TURNS);
+    (0..MAX_HISTORY_TURNS * 2).for_each(|_| playback.rewind());
+    assert_eq!(playback.shown().turn, MAX_HISTORY_TURNS + 1);
+
+    // Big games keep fewer turns, but always the latest one.
+    let mut playback = Playback::new(250, frame(0, HISTORY_POSITIONS / 2));
+    (1..=3).for_each(|turn| playback.push(frame(turn, HISTORY_POSITIONS / 2)));
+    assert_eq!(playback.frames.len(), 2);
+    assert_eq!(playback.latest().turn, 3);
+}
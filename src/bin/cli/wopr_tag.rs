@@ -1,8 +1,25 @@
 extern crate wopr_tag;
-use clap::{App, Arg};
+mod export;
+mod tui;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use export::{FrameExporter, FrameFormat};
 use log::{debug, error};
 use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::mpsc::{channel, Receiver};
+use std::{thread, time};
+use wopr_tag::html_report::render_html_report;
+use wopr_tag::image_renderer::{ImageRenderer, Palette};
+use wopr_tag::renderer;
+use wopr_tag::sweep;
+use wopr_tag::{Arena, ChaserStrategy, CollisionRule, Event, GameMode, HeatmapKind, JsonLinesSink, MoveResolution,
+               Replay, Simulation, SimulationConfig, Stats, StatsFormat, StatsReport, SweepSpec, TagNetwork, Topology,
+               TurnOrder, Vision};
 
 fn main() {
     let log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "INFO".to_owned());
@@ -16,6 +33,100 @@ fn process_command_line() {
         .version(env!("CARGO_PKG_VERSION"))
         .author(env!("CARGO_PKG_AUTHORS"))
         .about("Agent based simulation of the game tag")
+        // The options for a simulation are not needed to replay one.
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about(
+                    "Plays back a game recorded with --event-log, checking that everything in the log could have \
+                     happened."
+                )
+                .arg(
+                    Arg::with_name("event_log")
+                        .value_name("event_log")
+                        .help("Path to the event log to replay.")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("show_field")
+                        .value_name("show_field")
+                        .help("Should we display the field during the replay (true/false)")
+                        .short("s")
+                        .long("show-field")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("true")
+                        .validator(validate_bool)
+                )
+                .arg(
+                    Arg::with_name("wait_between_turn")
+                        .value_name("wait_between_turn")
+                        .help("How long to wait between turns in milliseconds.")
+                        .short("w")
+                        .long("wait-between-turn")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("250")
+                        .validator(validate_wait)
+                )
+                .arg(
+                    Arg::with_name("tui")
+                        .help("Replay in a full screen viewer instead of printing every turn.")
+                        .long("tui")
+                        .required(false)
+                        .takes_value(false)
+                )
+                .arg(
+                    Arg::with_name("turn")
+                        .value_name("turn")
+                        .help("The turn to start displaying the replay from. Earlier turns are still checked.")
+                        .long("turn")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("1")
+                        .validator(validate_turn)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("sweep")
+                .about(
+                    "Plays every combination of the parameters in a sweep spec without displaying anything and writes \
+                     a CSV with a row per game and summary statistics per combination."
+                )
+                .arg(
+                    Arg::with_name("spec")
+                        .value_name("spec")
+                        .help(
+                            "Path to a JSON file with the values to try for each parameter, the number of repetitions \
+                             and the base seed."
+                        )
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("out")
+                        .value_name("out")
+                        .help("Path to write the CSV to, overwriting it if it exists. Printed when not given.")
+                        .short("o")
+                        .long("out")
+                        .required(false)
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .value_name("threads")
+                        .help(
+                            "How many games to play at once. Defaults to the number of CPUs. The results are the same \
+                             for any number of threads."
+                        )
+                        .short("t")
+                        .long("threads")
+                        .required(false)
+                        .takes_value(true)
+                        .validator(validate_threads)
+                )
+        )
         .arg(
             Arg::with_name("number_of_players")
                 .value_name("number_of_players")
@@ -32,7 +143,8 @@ fn process_command_line() {
                 .help("How long should the field of play be along the x-axis.")
                 .short("x")
                 .long("x-size")
-                .required(true)
+                .required_unless("map")
+                .conflicts_with("map")
                 .takes_value(true)
                 .validator(validate_axis)
         )
@@ -42,10 +154,23 @@ fn process_command_line() {
                 .help("How long should the field of play be along the y-axis.")
                 .short("y")
                 .long("y-size")
-                .required(true)
+                .required_unless("map")
+                .conflicts_with("map")
                 .takes_value(true)
                 .validator(validate_axis)
         )
+        .arg(
+            Arg::with_name("map")
+                .value_name("map")
+                .help(
+                    "Path to a text file describing the field of play, used instead of --x-size and --y-size. Each \
+                     line is a row of the field. Use . for open ground, # for walls, T for trees, ~ for water, S for \
+                     places players may start and I for places it players may start."
+                )
+                .long("map")
+                .required(false)
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("show_field")
                 .value_name("show_field")
@@ -57,6 +182,16 @@ fn process_command_line() {
                 .default_value("true")
                 .validator(validate_bool)
         )
+        .arg(
+            Arg::with_name("tui")
+                .help(
+                    "Watch the game in a full screen viewer with colour, live stats and controls to pause, step, \
+                     change speed and scroll around large fields. Replaces --show-field."
+                )
+                .long("tui")
+                .required(false)
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("wait_between_turn")
                 .value_name("wait_between_turn")
@@ -82,30 +217,707 @@ fn process_command_line() {
                 .default_value("1000")
                 .validator(validate_num_turns)
         )
+        .arg(
+            Arg::with_name("seed")
+                .value_name("seed")
+                .help(
+                    "Seed for the random number generator. Running with the same seed and options reproduces the same \
+                     game. If omitted a random seed is chosen and logged."
+                )
+                .long("seed")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_seed)
+        )
+        .arg(
+            Arg::with_name("chaser_strategy")
+                .value_name("chaser_strategy")
+                .help(
+                    "How the it player chases other players. random: move randomly, nearest: go after the closest \
+                     player, cluster: go after the most crowded part of the field, pathfinding: find a route around \
+                     other players to the closest player."
+                )
+                .short("c")
+                .long("chaser-strategy")
+                .required(false)
+                .takes_value(true)
+                .default_value("random")
+                .validator(validate_chaser_strategy)
+        )
+        .arg(
+            Arg::with_name("game_mode")
+                .value_name("game_mode")
+                .help(
+                    "The rules to play by. classic: tagging someone makes them it. freeze: tagged players freeze in \
+                     place until another player rescues them, and the game ends when everyone is frozen. infection: \
+                     tagged players become it and stay it, and the game ends when everyone is it."
+                )
+                .short("m")
+                .long("game-mode")
+                .required(false)
+                .takes_value(true)
+                .default_value("classic")
+                .validator(validate_game_mode)
+        )
+        .arg(
+            Arg::with_name("num_it_players")
+                .value_name("num_it_players")
+                .help("How many players start the game as it.")
+                .long("num-it")
+                .required(false)
+                .takes_value(true)
+                .default_value("1")
+                .validator(validate_num_it_players)
+        )
+        .arg(
+            Arg::with_name("tag_back_cooldown")
+                .value_name("tag_back_cooldown")
+                .help(
+                    "How many turns a tagged player must wait before they can tag the player who tagged them. If \
+                     omitted they can not tag them back until someone else tags them."
+                )
+                .long("tag-back-cooldown")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_tag_back_cooldown)
+        )
+        .arg(
+            Arg::with_name("obstacle_density")
+                .value_name("obstacle_density")
+                .help(
+                    "The chance, between 0.0 and 1.0, of each position on the field being an obstacle such as a wall, \
+                     tree or water. Players can not move through obstacles."
+                )
+                .long("obstacle-density")
+                .required(false)
+                .takes_value(true)
+                .default_value("0.0")
+                .validator(validate_obstacle_density)
+        )
+        .arg(
+            Arg::with_name("topology")
+                .value_name("topology")
+                .help(
+                    "What happens at the edges of the field. bounded: the edges are walls. torus: players who walk \
+                     off any edge come back on at the opposite edge. wrap-x: only the left and right edges wrap. \
+                     wrap-y: only the top and bottom edges wrap."
+                )
+                .long("topology")
+                .required(false)
+                .takes_value(true)
+                .default_value("bounded")
+                .validator(validate_topology)
+        )
+        .arg(
+            Arg::with_name("turn_order")
+                .value_name("turn_order")
+                .help(
+                    "The order players act in each turn. fixed: by index, so p0 always acts first. shuffled: a new \
+                     random order every turn. it-first: it players act before everyone else. it-last: it players act \
+                     after everyone else. initiative: players roll initiative once at the start of the game and act \
+                     in that order."
+                )
+                .long("turn-order")
+                .required(false)
+                .takes_value(true)
+                .default_value("fixed")
+                .validator(validate_turn_order)
+        )
+        .arg(
+            Arg::with_name("move_resolution")
+                .value_name("move_resolution")
+                .help(
+                    "How the players' actions in a turn are carried out. sequential: one player at a time, each \
+                     seeing the moves of the players before them. simultaneous: every player decides looking at the \
+                     field as it was at the start of the turn and everyone moves at once."
+                )
+                .long("move-resolution")
+                .required(false)
+                .takes_value(true)
+                .default_value("sequential")
+                .validator(validate_move_resolution)
+        )
+        .arg(
+            Arg::with_name("collision_rule")
+                .value_name("collision_rule")
+                .help(
+                    "Who moves when simultaneous moves collide, by moving to the same position or through each other. \
+                     random-priority: one of them at random. bounce: nobody. risk-tolerance: the player with the \
+                     highest risk tolerance."
+                )
+                .long("collision-rule")
+                .required(false)
+                .takes_value(true)
+                .default_value("random-priority")
+                .validator(validate_collision_rule)
+        )
+        .arg(
+            Arg::with_name("max_speed")
+                .value_name("max_speed")
+                .help(
+                    "The most positions a player can move in a turn. Moving more than one position costs a stamina \
+                     for every position after the first. Players who are it always sprint and everyone else sprints \
+                     when the it player is close."
+                )
+                .long("max-speed")
+                .required(false)
+                .takes_value(true)
+                .default_value("1")
+                .validator(validate_max_speed)
+        )
+        .arg(
+            Arg::with_name("max_stamina")
+                .value_name("max_stamina")
+                .help("How much stamina players start with. Standing still for a turn recovers 2, up to this amount.")
+                .long("max-stamina")
+                .required(false)
+                .takes_value(true)
+                .default_value("10")
+                .validator(validate_max_stamina)
+        )
+        .arg(
+            Arg::with_name("random_fitness")
+                .help(
+                    "Give every player a random max speed between 1 and --max-speed and a random max stamina between \
+                     0 and --max-stamina."
+                )
+                .long("random-fitness")
+                .required(false)
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("vision_range")
+                .value_name("vision_range")
+                .help(
+                    "How many positions away players can see. Players face the way they last moved and only know \
+                     where the it player is once they have seen them. If omitted players always know where the it \
+                     player is."
+                )
+                .long("vision-range")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_vision_range)
+        )
+        .arg(
+            Arg::with_name("vision_angle")
+                .value_name("vision_angle")
+                .help("How wide, in degrees, the cone players see in front of them is. Only used with --vision-range.")
+                .long("vision-angle")
+                .required(false)
+                .takes_value(true)
+                .default_value("120")
+                .validator(validate_vision_angle)
+        )
+        .arg(
+            Arg::with_name("frames_dir")
+                .value_name("frames_dir")
+                .help("Directory to save an image of every turn to, in the format set by --frame-format.")
+                .long("frames-dir")
+                .required(false)
+                .takes_value(true)
+                .conflicts_with("tui")
+        )
+        .arg(
+            Arg::with_name("frame_format")
+                .value_name("frame_format")
+                .help("The image format used by --frames-dir. svg or png.")
+                .long("frame-format")
+                .required(false)
+                .takes_value(true)
+                .default_value("svg")
+                .validator(validate_frame_format)
+        )
+        .arg(
+            Arg::with_name("gif")
+                .value_name("gif")
+                .help("Path to save an animated GIF of the whole game to.")
+                .long("gif")
+                .required(false)
+                .takes_value(true)
+                .conflicts_with("tui")
+        )
+        .arg(
+            Arg::with_name("gif_frame_delay")
+                .value_name("gif_frame_delay")
+                .help("How long each turn is shown for in the GIF, in milliseconds.")
+                .long("gif-frame-delay")
+                .required(false)
+                .takes_value(true)
+                .default_value("100")
+                .validator(validate_wait)
+        )
+        .arg(
+            Arg::with_name("cell_size")
+                .value_name("cell_size")
+                .help("How many pixels wide each position on the field is in images and GIFs.")
+                .long("cell-size")
+                .required(false)
+                .takes_value(true)
+                .default_value("8")
+                .validator(validate_cell_size)
+        )
+        .arg(
+            Arg::with_name("palette")
+                .value_name("palette")
+                .help(
+                    "Colours for images and GIFs as a comma separated list of name=#rrggbb, for example \
+                     it=#ff0000,tag=#ffff00. The names are background, obstacle, player, it, frozen and tag, which \
+                     outlines players who were just tagged."
+                )
+                .long("palette")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_palette)
+        )
+        .arg(
+            Arg::with_name("event_log")
+                .value_name("event_log")
+                .help(
+                    "Path to write a log of everything that happens during the game to, one JSON object per line. The \
+                     file is overwritten if it exists."
+                )
+                .long("event-log")
+                .required(false)
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("heatmap_dir")
+                .value_name("heatmap_dir")
+                .help(
+                    "Directory to save heatmaps of where players stood, where it players stood and where tags \
+                     happened to once the game ends, as CSV and PNG."
+                )
+                .long("heatmap-dir")
+                .required(false)
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("show_heatmaps")
+                .help(
+                    "Print heatmaps of where players stood, where it players stood and where tags happened once the \
+                     game ends. Needs a terminal that supports 24-bit colour."
+                )
+                .long("show-heatmaps")
+                .required(false)
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("tag_network")
+                .value_name("tag_network")
+                .help(
+                    "Path to save the graph of who tagged whom to once the game ends. The format is picked from the \
+                     extension: .dot or .gv for Graphviz DOT and .graphml for GraphML."
+                )
+                .long("tag-network")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_tag_network)
+        )
+        .arg(
+            Arg::with_name("stats_format")
+                .value_name("stats_format")
+                .help(
+                    "How to write the stats at the end of the game. table lines the stats up in columns for reading, \
+                     json and csv are for scripts."
+                )
+                .long("stats-format")
+                .required(false)
+                .takes_value(true)
+                .default_value("table")
+                .validator(validate_stats_format)
+        )
+        .arg(
+            Arg::with_name("stats_out")
+                .value_name("stats_out")
+                .help("Path to write the stats to instead of printing them. The file is overwritten if it exists.")
+                .long("stats-out")
+                .required(false)
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("html_report")
+                .value_name("html_report")
+                .help(
+                    "Path to write a standalone HTML page to once the game ends, for exploring the game in a browser. \
+                     The file is overwritten if it exists."
+                )
+                .long("html-report")
+                .required(false)
+                .takes_value(true)
+        )
         .get_matches();
 
+    if let Some(replay_matches) = matches.subcommand_matches("replay") {
+        exit_on_error(process_replay(replay_matches));
+        return;
+    }
+    if let Some(sweep_matches) = matches.subcommand_matches("sweep") {
+        exit_on_error(process_sweep(sweep_matches));
+        return;
+    }
+
     // Unwrapping here is safe because we have already validated the inputs via Clap's
     // validation functionality.
     let num_players = matches.value_of("number_of_players").unwrap().parse::<usize>().unwrap();
-    let x_size = matches.value_of("x_size").unwrap().parse::<usize>().unwrap();
-    let y_size = matches.value_of("y_size").unwrap().parse::<usize>().unwrap();
+    // The axis lengths are only missing when a map is given, in which case the map decides them.
+    let x_size = matches
+        .value_of("x_size")
+        .map_or(0, |x_size| x_size.parse::<usize>().unwrap());
+    let y_size = matches
+        .value_of("y_size")
+        .map_or(0, |y_size| y_size.parse::<usize>().unwrap());
     let wait = matches.value_of("wait_between_turn").unwrap().parse::<u64>().unwrap();
     let show_field = matches.value_of("show_field").unwrap().parse::<bool>().unwrap();
     let num_turns = matches.value_of("num_turns").unwrap().parse::<usize>().unwrap();
+    let seed = matches.value_of("seed").map(|seed| seed.parse::<u64>().unwrap());
+    let chaser_strategy = matches
+        .value_of("chaser_strategy")
+        .unwrap()
+        .parse::<ChaserStrategy>()
+        .unwrap();
+    let game_mode = matches.value_of("game_mode").unwrap().parse::<GameMode>().unwrap();
+    let num_it_players = matches.value_of("num_it_players").unwrap().parse::<usize>().unwrap();
+    let tag_back_cooldown = matches
+        .value_of("tag_back_cooldown")
+        .map(|cooldown| cooldown.parse::<usize>().unwrap());
+    let obstacle_density = matches.value_of("obstacle_density").unwrap().parse::<f64>().unwrap();
+    let topology = matches.value_of("topology").unwrap().parse::<Topology>().unwrap();
+    let turn_order = matches.value_of("turn_order").unwrap().parse::<TurnOrder>().unwrap();
+    let move_resolution = matches
+        .value_of("move_resolution")
+        .unwrap()
+        .parse::<MoveResolution>()
+        .unwrap();
+    let collision_rule = matches
+        .value_of("collision_rule")
+        .unwrap()
+        .parse::<CollisionRule>()
+        .unwrap();
+    let max_speed = matches.value_of("max_speed").unwrap().parse::<usize>().unwrap();
+    let max_stamina = matches.value_of("max_stamina").unwrap().parse::<usize>().unwrap();
+    let random_fitness = matches.is_present("random_fitness");
+    let vision_angle = matches.value_of("vision_angle").unwrap().parse::<u32>().unwrap();
+    let vision = matches.value_of("vision_range").map(|range| Vision {
+        range: range.parse::<usize>().unwrap(),
+        angle: vision_angle
+    });
+    let arena = match matches
+        .value_of("map")
+        .map(|map| Arena::from_file(Path::new(map)))
+        .transpose()
+    {
+        Ok(arena) => arena,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
 
+    let config = SimulationConfig {
+        num_players,
+        x_axis_len: x_size,
+        y_axis_len: y_size,
+        num_turns,
+        seed,
+        chaser_strategy,
+        game_mode,
+        num_it_players,
+        tag_back_cooldown,
+        obstacle_density,
+        arena,
+        topology,
+        turn_order,
+        move_resolution,
+        collision_rule,
+        max_speed,
+        max_stamina,
+        random_fitness,
+        vision
+    };
     debug!(
-        "cli args - number_of_players: {}, x_size: {}, y_size: {}, wait: {}, show_field: {}, num_turns: {}",
-        num_players, x_size, y_size, wait, show_field, num_turns
+        "cli args - wait: {}, show_field: {}, config: {:?}",
+        wait, show_field, config
     );
 
-    if x_size * y_size < num_players {
-        error!(
-            "{} players cannot fit on field of dimenstions {} x {}",
-            num_players, x_size, y_size
-        );
+    let mut simulation = match Simulation::new(config) {
+        Ok(simulation) => simulation,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
+    if let Some(event_log) = matches.value_of("event_log") {
+        match File::create(event_log) {
+            Ok(file) => simulation.add_event_sink(Box::new(JsonLinesSink::new(BufWriter::new(file)))),
+            Err(err) => {
+                error!("could not create event log {}: {}", event_log, err);
+                return;
+            }
+        }
+    }
+
+    let html_report = matches.value_of("html_report").map(|html_report| {
+        let (sender, events) = channel();
+        simulation.add_event_sink(Box::new(sender));
+        (PathBuf::from(html_report), events)
+    });
+
+    let stats_format = matches
+        .value_of("stats_format")
+        .unwrap()
+        .parse::<StatsFormat>()
+        .unwrap();
+    let stats_out = matches.value_of("stats_out").map(Path::new);
+
+    let simulation = if matches.is_present("tui") {
+        if let Err(err) = tui::run(&mut simulation, wait) {
+            error!("{}", err);
+            return;
+        }
+        simulation
     } else {
-        wopr_tag::init(num_players, x_size, y_size, wait, show_field, num_turns);
+        match start_frame_exporter(&matches, &mut simulation) {
+            Ok(exporter) => run_simulation(simulation, wait, show_field, exporter),
+            Err(err) => {
+                error!("{}", err);
+                return;
+            }
+        }
+    };
+
+    if let Some((path, events)) = html_report {
+        write_html_report(&path, events, simulation.stats());
+    }
+    if let Some(heatmap_dir) = matches.value_of("heatmap_dir") {
+        if let Err(err) = write_heatmaps(Path::new(heatmap_dir), &image_renderer(&matches), &simulation) {
+            error!("{}", err);
+        }
+    }
+    if let Some(tag_network) = matches.value_of("tag_network") {
+        write_tag_network(Path::new(tag_network), simulation.stats().tag_network());
+    }
+    if matches.is_present("show_heatmaps") {
+        for kind in HeatmapKind::ALL.iter() {
+            print!(
+                "{}",
+                renderer::render_heatmap(simulation.stats().heatmap(*kind), *kind, simulation.field_of_play())
+            );
+        }
+    }
+    write_stats(&simulation, stats_format, stats_out);
+}
+
+// Returns None when no images were asked for.
+fn start_frame_exporter(matches: &ArgMatches, simulation: &mut Simulation) -> Result<Option<FrameExporter>, String> {
+    let frames = matches.value_of("frames_dir").map(|frames_dir| {
+        let frame_format = matches
+            .value_of("frame_format")
+            .unwrap()
+            .parse::<FrameFormat>()
+            .unwrap();
+        (PathBuf::from(frames_dir), frame_format)
+    });
+    let gif = matches.value_of("gif").map(|gif| {
+        let frame_delay = matches.value_of("gif_frame_delay").unwrap().parse::<u64>().unwrap();
+        (PathBuf::from(gif), frame_delay)
+    });
+    if frames.is_none() && gif.is_none() {
+        return Ok(None);
+    }
+
+    FrameExporter::new(simulation, image_renderer(matches), frames, gif).map(Some)
+}
+
+fn image_renderer(matches: &ArgMatches) -> ImageRenderer {
+    ImageRenderer {
+        cell_size: matches.value_of("cell_size").unwrap().parse::<u32>().unwrap(),
+        palette: matches
+            .value_of("palette")
+            .map_or_else(Palette::default, |palette| palette.parse::<Palette>().unwrap())
+    }
+}
+
+// Writes every heatmap as a CSV matrix and a PNG image.
+fn write_heatmaps(heatmap_dir: &Path, image_renderer: &ImageRenderer, simulation: &Simulation) -> Result<(), String> {
+    std::fs::create_dir_all(heatmap_dir)
+        .map_err(|err| format!("could not create {}: {}", heatmap_dir.display(), err))?;
+    for kind in HeatmapKind::ALL.iter() {
+        let heatmap = simulation.stats().heatmap(*kind);
+        let csv_path = heatmap_dir.join(format!("{}.csv", kind.name()));
+        std::fs::write(&csv_path, heatmap.to_csv())
+            .map_err(|err| format!("could not write {}: {}", csv_path.display(), err))?;
+        let png_path = heatmap_dir.join(format!("{}.png", kind.name()));
+        let file = File::create(&png_path).map_err(|err| format!("could not write {}: {}", png_path.display(), err))?;
+        image_renderer
+            .write_heatmap_png(BufWriter::new(file), heatmap, simulation.field_of_play())
+            .map_err(|err| format!("could not write {}: {}", png_path.display(), err))?;
     }
+
+    Ok(())
+}
+
+fn run_simulation(
+    mut simulation: Simulation,
+    wait_between_turn_ms: u64,
+    show_field: bool,
+    mut exporter: Option<FrameExporter>
+) -> Simulation {
+    let sleep_between_turn_dur = time::Duration::from_millis(wait_between_turn_ms);
+    while simulation.step() {
+        if let Some(frame_exporter) = &mut exporter {
+            if let Err(err) = frame_exporter.export_turn(&simulation) {
+                error!("{}", err);
+                exporter = None;
+            }
+        }
+        if show_field {
+            print!(
+                "{}",
+                renderer::render_field(simulation.field_of_play(), simulation.players(), simulation.turn_num())
+            );
+        }
+
+        thread::sleep(sleep_between_turn_dur);
+    }
+
+    if let Some(Err(err)) = exporter.map(FrameExporter::finish) {
+        error!("{}", err);
+    }
+
+    simulation
+}
+
+fn write_tag_network(path: &Path, tag_network: &TagNetwork) {
+    // The extension has already been checked by validate_tag_network.
+    let graph = if path.extension().is_some_and(|extension| extension == "graphml") {
+        tag_network.to_graphml()
+    } else {
+        tag_network.to_dot()
+    };
+    if let Err(err) = std::fs::write(path, graph) {
+        error!("could not write tag network {}: {}", path.display(), err);
+    }
+}
+
+// Prints the stats, or writes them to `stats_out` if it is given.
+fn write_stats(simulation: &Simulation, stats_format: StatsFormat, stats_out: Option<&Path>) {
+    let stats = StatsReport::new(simulation.config(), simulation.stats()).render(stats_format);
+    match stats_out {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, stats) {
+                error!("could not write stats to {}: {}", path.display(), err);
+            }
+        }
+        None => print!("{}", stats)
+    }
+}
+
+// The report is only written once the game is over, so every event is already in the channel.
+fn write_html_report(path: &Path, events: Receiver<Event>, stats: &Stats) {
+    let result = render_html_report(events.try_iter().collect(), stats)
+        .and_then(|html| std::fs::write(path, html).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        error!("could not write HTML report {}: {}", path.display(), err);
+    }
+}
+
+// Replays and sweeps are run from scripts, so they exit with a failure status if anything goes
+// wrong.
+fn exit_on_error(result: Result<(), String>) {
+    if let Err(err) = result {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn process_replay(matches: &ArgMatches) -> Result<(), String> {
+    // Unwrapping here is safe because we have already validated the inputs via Clap's
+    // validation functionality.
+    let event_log = matches.value_of("event_log").unwrap();
+    let show_field = matches.value_of("show_field").unwrap().parse::<bool>().unwrap();
+    let wait = matches.value_of("wait_between_turn").unwrap().parse::<u64>().unwrap();
+    let turn = matches.value_of("turn").unwrap().parse::<usize>().unwrap();
+
+    let mut replay = Replay::from_file(Path::new(event_log))?;
+    if matches.is_present("tui") {
+        replay
+            .skip_to_turn(turn - 1)
+            .map_err(|err| format!("The event log is inconsistent: {}", err))?;
+        return match tui::run(&mut replay, wait) {
+            Ok(None) => Ok(()),
+            Ok(Some(err)) => Err(format!("The event log is inconsistent: {}", err)),
+            Err(err) => Err(err.to_string())
+        };
+    }
+
+    run_replay(replay, wait, show_field, turn)
+}
+
+fn process_sweep(matches: &ArgMatches) -> Result<(), String> {
+    // Unwrapping here is safe because the spec is required.
+    let spec = SweepSpec::from_file(Path::new(matches.value_of("spec").unwrap()))?;
+
+    // 0 lets Rayon use every CPU.
+    let threads = matches
+        .value_of("threads")
+        .map_or(0, |threads| threads.parse::<usize>().unwrap());
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|err| format!("could not start the threads for the sweep: {}", err))?;
+
+    // Every game logs as it starts and on every tag, which would bury the progress.
+    let log_level = log::max_level();
+    log::set_max_level(log_level.min(log::LevelFilter::Warn));
+    let runs = thread_pool.install(|| {
+        sweep::run_sweep(&spec, |played, total| {
+            eprint!("\rplayed {} of {} games", played, total);
+        })
+    });
+    eprintln!();
+    log::set_max_level(log_level);
+
+    let csv = sweep::sweep_to_csv(&runs?);
+    match matches.value_of("out") {
+        Some(path) => {
+            std::fs::write(path, csv).map_err(|err| format!("could not write sweep results to {}: {}", path, err))
+        }
+        None => {
+            print!("{}", csv);
+            Ok(())
+        }
+    }
+}
+
+fn run_replay(
+    mut replay: Replay,
+    wait_between_turn_ms: u64,
+    show_field: bool,
+    start_turn: usize
+) -> Result<(), String> {
+    let sleep_between_turn_dur = time::Duration::from_millis(wait_between_turn_ms);
+    let mut result = replay.skip_to_turn(start_turn - 1);
+    while result.is_ok() {
+        match replay.step() {
+            Ok(true) => {
+                if show_field {
+                    print!(
+                        "{}",
+                        renderer::render_field(replay.field_of_play(), replay.players(), replay.turn_num())
+                    );
+                }
+
+                thread::sleep(sleep_between_turn_dur);
+            }
+            Ok(false) => break,
+            Err(err) => result = Err(err)
+        }
+    }
+
+    result.map_err(|err| format!("The event log is inconsistent: {}", err))?;
+    println!("Replayed {} turns. The event log is consistent.", replay.turn_num());
+
+    Ok(())
 }
 
 fn validate_num_players(players: String) -> Result<(), String> {
@@ -179,3 +991,163 @@ fn validate_num_turns(turns: String) -> Result<(), String> {
 
     Err(err_msg)
 }
+
+fn validate_seed(seed: String) -> Result<(), String> {
+    if seed.parse::<u64>().is_ok() {
+        return Ok(());
+    }
+
+    let err_msg = format!("the seed must be a valid integer between 0 - {} inclusive.", u64::MAX);
+
+    Err(err_msg)
+}
+
+fn validate_chaser_strategy(chaser_strategy: String) -> Result<(), String> {
+    chaser_strategy.parse::<ChaserStrategy>().map(|_| ())
+}
+
+fn validate_game_mode(game_mode: String) -> Result<(), String> {
+    game_mode.parse::<GameMode>().map(|_| ())
+}
+
+fn validate_num_it_players(num_it_players: String) -> Result<(), String> {
+    if let Ok(num_it_players) = num_it_players.parse::<usize>() {
+        if num_it_players >= 1 {
+            return Ok(());
+        }
+    };
+
+    Err("the number of it players must be a valid integer of at least 1.".to_owned())
+}
+
+fn validate_tag_back_cooldown(cooldown: String) -> Result<(), String> {
+    if cooldown.parse::<usize>().is_ok() {
+        return Ok(());
+    }
+
+    let err_msg = format!(
+        "the tag-back cooldown must be a valid integer between 0 - {} inclusive.",
+        usize::MAX
+    );
+
+    Err(err_msg)
+}
+
+fn validate_obstacle_density(density: String) -> Result<(), String> {
+    if let Ok(density) = density.parse::<f64>() {
+        if (0.0..=1.0).contains(&density) {
+            return Ok(());
+        }
+    };
+
+    Err("the obstacle density must be a number between 0.0 - 1.0 inclusive.".to_owned())
+}
+
+fn validate_topology(topology: String) -> Result<(), String> {
+    topology.parse::<Topology>().map(|_| ())
+}
+
+fn validate_turn_order(turn_order: String) -> Result<(), String> {
+    turn_order.parse::<TurnOrder>().map(|_| ())
+}
+
+fn validate_move_resolution(move_resolution: String) -> Result<(), String> {
+    move_resolution.parse::<MoveResolution>().map(|_| ())
+}
+
+fn validate_collision_rule(collision_rule: String) -> Result<(), String> {
+    collision_rule.parse::<CollisionRule>().map(|_| ())
+}
+
+fn validate_max_speed(max_speed: String) -> Result<(), String> {
+    if let Ok(max_speed) = max_speed.parse::<usize>() {
+        if max_speed >= 1 {
+            return Ok(());
+        }
+    };
+
+    Err("the max speed must be a valid integer of at least 1.".to_owned())
+}
+
+fn validate_max_stamina(max_stamina: String) -> Result<(), String> {
+    if max_stamina.parse::<usize>().is_ok() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "the max stamina must be a valid integer between 0 - {} inclusive.",
+        usize::MAX
+    ))
+}
+
+fn validate_vision_range(vision_range: String) -> Result<(), String> {
+    if let Ok(vision_range) = vision_range.parse::<usize>() {
+        if vision_range >= 1 {
+            return Ok(());
+        }
+    };
+
+    Err("the vision range must be a valid integer of at least 1.".to_owned())
+}
+
+fn validate_vision_angle(vision_angle: String) -> Result<(), String> {
+    if let Ok(vision_angle) = vision_angle.parse::<u32>() {
+        if (1..=360).contains(&vision_angle) {
+            return Ok(());
+        }
+    };
+
+    Err("the vision angle must be a valid integer between 1 - 360 inclusive.".to_owned())
+}
+
+fn validate_threads(threads: String) -> Result<(), String> {
+    if let Ok(threads) = threads.parse::<usize>() {
+        if threads >= 1 {
+            return Ok(());
+        }
+    };
+
+    Err("the number of threads must be a valid integer of at least 1.".to_owned())
+}
+
+fn validate_turn(turn: String) -> Result<(), String> {
+    if let Ok(turn) = turn.parse::<usize>() {
+        if turn >= 1 {
+            return Ok(());
+        }
+    };
+
+    Err("the turn must be a valid integer of at least 1.".to_owned())
+}
+
+fn validate_frame_format(frame_format: String) -> Result<(), String> {
+    frame_format.parse::<FrameFormat>().map(|_| ())
+}
+
+fn validate_cell_size(cell_size: String) -> Result<(), String> {
+    if let Ok(cell_size) = cell_size.parse::<u32>() {
+        if (1..=100).contains(&cell_size) {
+            return Ok(());
+        }
+    };
+
+    Err("the cell size must be a valid integer between 1 - 100 inclusive.".to_owned())
+}
+
+fn validate_palette(palette: String) -> Result<(), String> {
+    palette.parse::<Palette>().map(|_| ())
+}
+
+fn validate_stats_format(stats_format: String) -> Result<(), String> {
+    stats_format.parse::<StatsFormat>().map(|_| ())
+}
+
+fn validate_tag_network(tag_network: String) -> Result<(), String> {
+    match Path::new(&tag_network)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("dot") | Some("gv") | Some("graphml") => Ok(()),
+        _ => Err("the tag network file must end in .dot, .gv or .graphml".to_owned())
+    }
+}
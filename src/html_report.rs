@@ -0,0 +1,280 @@
+use crate::image_renderer::Palette;
+use crate::models::event::Event;
+use crate::models::player::Player;
+use crate::models::resolution::MoveResolution;
+use crate::models::rules::GameMode;
+use crate::models::stats::Stats;
+use crate::replay::Replay;
+use serde_json::json;
+use std::fmt::Write;
+
+const TEMPLATE: &str = include_str!("html_report.html");
+
+// The state of a player in a frame.
+const RUNNING: u8 = 0;
+const IT: u8 = 1;
+const FROZEN: u8 = 2;
+
+/// Builds a single HTML page to explore a finished game in a browser. The page needs nothing else to
+/// work: every turn is embedded in it and played back on a canvas, with controls to play, pause and
+/// scrub through the turns, a timeline of every tag and rescue and a table of the final `stats`.
+///
+/// `events` must be the complete event log of the game, starting with
+/// [Event::GameStarted] and ending with [Event::GameEnded]. It is replayed to build the turns, so an
+/// inconsistent log is reported as an error.
+pub fn render_html_report(events: Vec<Event>, stats: &Stats) -> Result<String, String> {
+    let (seed, topology, num_turns, terrain) = match events.first() {
+        Some(Event::GameStarted {
+            seed,
+            topology,
+            num_turns,
+            terrain,
+            ..
+        }) => (*seed, *topology, *num_turns, terrain.clone()),
+        _ => return Err("the event log must start with game_started".to_owned())
+    };
+    let timeline: Vec<_> = events
+        .iter()
+        .filter_map(|event| match *event {
+            Event::Tagged {
+                turn,
+                player,
+                tagged_player,
+                ..
+            } => Some(json!({"turn": turn, "kind": "tagged", "player": player, "other": tagged_player})),
+            Event::Rescued {
+                turn,
+                player,
+                rescued_player,
+                ..
+            } => Some(json!({"turn": turn, "kind": "rescued", "player": player, "other": rescued_player})),
+            _ => None
+        })
+        .collect();
+
+    let mut replay = Replay::new(events)?;
+    let mut frames = vec![frame(replay.players())];
+    while replay.step()? {
+        frames.push(frame(replay.players()));
+    }
+
+    let palette = Palette::default();
+    let data = json!({
+        "game_mode": replay.game_mode().to_string(),
+        "terrain": terrain,
+        "frames": frames,
+        "timeline": timeline,
+        "colors": {
+            "background": palette.background.to_hex(),
+            "obstacle": palette.obstacle.to_hex(),
+            "player": palette.player.to_hex(),
+            "it": palette.it.to_hex(),
+            "frozen": palette.frozen.to_hex(),
+            "tag": palette.tag.to_hex()
+        }
+    });
+    // A "</" in the data would end the script element it is embedded in. JSON allows the slash to
+    // be escaped, so the browser reads the same data.
+    let data = data.to_string().replace("</", "<\\/");
+
+    let title = format!("{} tag, seed {}", stats.game_mode(), seed);
+    let mut summary = vec![
+        ("game mode", stats.game_mode().to_string()),
+        ("seed", seed.to_string()),
+        ("topology", topology.to_string()),
+        ("chaser strategy", stats.chaser_strategy().to_owned()),
+        ("turn order", stats.turn_order().to_string()),
+        ("turns played", format!("{} of {}", stats.turns_played(), num_turns)),
+        ("tags", stats.tag_count().to_string()),
+        ("tags per 100 turns", format!("{:.2}", stats.tags_per_turn() * 100.0)),
+        (
+            "mean turns between tags",
+            format_optional(stats.mean_turns_between_tags())
+        ),
+    ];
+    if stats.move_resolution() == MoveResolution::Simultaneous {
+        summary.push(("collision rule", stats.collision_rule().to_string()));
+        summary.push((
+            "conflicts",
+            format!(
+                "{} for the same position, {} moving through each other",
+                stats.same_cell_conflicts(),
+                stats.swap_conflicts()
+            )
+        ));
+    }
+    if stats.can_sprint() {
+        summary.push(("stamina spent sprinting", stats.stamina_spent().to_string()));
+    }
+    if let Some(vision) = stats.vision() {
+        summary.push((
+            "vision",
+            format!("{} positions, {} degrees", vision.range, vision.angle)
+        ));
+    }
+    if let Some(turn) = stats.all_frozen_turn() {
+        summary.push(("everyone frozen on turn", turn.to_string()));
+    }
+    if let Some(turn) = stats.all_infected_turn() {
+        summary.push(("everyone infected on turn", turn.to_string()));
+    }
+
+    Ok(TEMPLATE
+        .replace("{{title}}", &escape(&title))
+        .replace("{{summary}}", &summary_rows(&summary))
+        .replace("{{stats_table}}", &stats_table(stats))
+        .replace("{{correlations}}", &correlation_rows(stats))
+        .replace("{{data}}", &data))
+}
+
+// Every player as x, y and state, one after the other.
+fn frame(players: &[Player]) -> Vec<usize> {
+    players
+        .iter()
+        .flat_map(|player| {
+            let (x, y) = player.get_location();
+            let state = if player.is_it {
+                IT
+            } else if player.is_frozen {
+                FROZEN
+            } else {
+                RUNNING
+            };
+            vec![x, y, state as usize]
+        })
+        .collect()
+}
+
+fn summary_rows(summary: &[(&str, String)]) -> String {
+    let mut rows = String::new();
+    for (name, value) in summary {
+        // Writing to a String can not fail.
+        let _ = writeln!(rows, "<tr><th>{}</th><td>{}</td></tr>", name, escape(value));
+    }
+
+    rows
+}
+
+fn stats_table(stats: &Stats) -> String {
+    let mut columns = vec!["player", "risk tolerance", "rounds started as it", "rounds made it"];
+    if stats.game_mode() == GameMode::Freeze {
+        columns.extend(&["times frozen", "rescues"]);
+    }
+    columns.extend(&[
+        "distance moved",
+        "turns stuck",
+        "longest it streak",
+        "mean turns to tag",
+        "tags given",
+        "tags received",
+        "mean distance from it",
+        "mean turn position"
+    ]);
+    if stats.can_sprint() {
+        columns.extend(&["max speed", "max stamina", "stamina spent"]);
+    }
+    if stats.vision().is_some() {
+        columns.push("it sightings");
+    }
+    let mut table = String::from("<thead><tr>");
+    for column in &columns {
+        let _ = write!(table, "<th>{}</th>", column);
+    }
+    table.push_str("</tr></thead>\n<tbody>\n");
+    for (name, player_stats) in stats.player_stats() {
+        let mut cells = vec![
+            escape(name),
+            format!("{:.2}", player_stats.risk_tolerance),
+            player_stats.rounds_started_as_it.to_string(),
+            player_stats.rounds_made_it.to_string(),
+        ];
+        if stats.game_mode() == GameMode::Freeze {
+            cells.push(player_stats.times_frozen.to_string());
+            cells.push(player_stats.rescues.to_string());
+        }
+        cells.extend(vec![
+            player_stats.distance_moved.to_string(),
+            player_stats.turns_stuck.to_string(),
+            player_stats.longest_it_streak.to_string(),
+            format_optional(player_stats.mean_turns_to_tag()),
+            player_stats.tags_given.to_string(),
+            player_stats.tags_received.to_string(),
+            format_optional(player_stats.mean_distance_from_it()),
+            format_optional(player_stats.mean_turn_position()),
+        ]);
+        if stats.can_sprint() {
+            cells.push(player_stats.max_speed.to_string());
+            cells.push(player_stats.max_stamina.to_string());
+            cells.push(player_stats.stamina_spent.to_string());
+        }
+        if stats.vision().is_some() {
+            cells.push(player_stats.it_sightings.to_string());
+        }
+        let _ = writeln!(table, "<tr><td>{}</td></tr>", cells.join("</td><td>"));
+    }
+    table.push_str("</tbody>");
+
+    table
+}
+
+fn correlation_rows(stats: &Stats) -> String {
+    let mut rows = String::new();
+    for (name, correlation) in stats.risk_tolerance_correlations() {
+        let _ = writeln!(
+            rows,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            name.replace('_', " "),
+            format_optional(correlation)
+        );
+    }
+
+    rows
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map_or_else(|| "n/a".to_owned(), |value| format!("{:.2}", value))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+use crate::simulation::{Simulation, SimulationConfig};
+#[cfg(test)]
+use std::sync::mpsc::channel;
+
+#[test]
+fn render_html_report_test() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        num_players: 4,
+        x_axis_len: 6,
+        y_axis_len: 5,
+        num_turns: 30,
+        seed: Some(3),
+        ..SimulationConfig::default()
+    })
+    .unwrap();
+    let (sender, receiver) = channel();
+    simulation.add_event_sink(Box::new(sender));
+    simulation.run_until(|_| false);
+    let events: Vec<Event> = receiver.try_iter().collect();
+
+    let html = render_html_report(events.clone(), simulation.stats()).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("{{"));
+    assert!(html.contains("<title>classic tag, seed 3</title>"));
+    assert!(html.contains("<tr><th>turns played</th><td>30 of 30</td></tr>"));
+    assert_eq!(html.matches("<tr><td>p").count(), 4);
+    // Turn 0 and every turn played.
+    assert!(html.contains(r#""frames":[["#));
+    assert_eq!(html.matches("],[").count(), 30);
+
+    assert_eq!(
+        render_html_report(events[1..].to_vec(), simulation.stats()).unwrap_err(),
+        "the event log must start with game_started"
+    );
+}
@@ -0,0 +1,484 @@
+use crate::models::field_of_play::FieldOfPlay;
+use crate::models::heatmap::Heatmap;
+use crate::models::player::Player;
+use std::convert::TryFrom;
+use std::fmt::Write as FmtWrite;
+use std::io::Write;
+use std::str::FromStr;
+
+/// A colour as red, green and blue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub(crate) fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    /// Parses a colour written as `#rrggbb`.
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid colour: {}. Must be written as #rrggbb", hex);
+        let digits = hex
+            .strip_prefix('#')
+            .filter(|digits| digits.len() == 6)
+            .ok_or_else(err)?;
+        let channel = |start: usize| {
+            digits
+                .get(start..start + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                .ok_or_else(err)
+        };
+
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+/// The colours used to draw the field of play.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Palette {
+    pub background: Rgb,
+    pub obstacle: Rgb,
+    pub player: Rgb,
+    pub it: Rgb,
+    pub frozen: Rgb,
+    /// Drawn around players who were tagged on the turn being drawn.
+    pub tag: Rgb
+}
+
+impl Palette {
+    pub const NAMES: [&'static str; 6] = ["background", "obstacle", "player", "it", "frozen", "tag"];
+
+    // The order here is the order of the colours in indexed images, so the index of a colour can be
+    // used as a pixel value.
+    fn colors(&self) -> [Rgb; 6] {
+        [
+            self.background,
+            self.obstacle,
+            self.player,
+            self.it,
+            self.frozen,
+            self.tag
+        ]
+    }
+
+    /// The colour of a heatmap position with `count` out of a highest count of `max`. Counts go from
+    /// the background colour at 0, through the tag colour at half of `max`, to the it colour at
+    /// `max`.
+    pub fn heat_color(&self, count: usize, max: usize) -> Rgb {
+        if max == 0 {
+            return self.background;
+        }
+        let fraction = count as f64 / max as f64;
+        let (from, to, fraction) = if fraction < 0.5 {
+            (self.background, self.tag, fraction * 2.0)
+        } else {
+            (self.tag, self.it, (fraction - 0.5) * 2.0)
+        };
+        let channel = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * fraction).round() as u8;
+
+        Rgb(channel(from.0, to.0), channel(from.1, to.1), channel(from.2, to.2))
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            background: Rgb(0xf4, 0xf1, 0xe8),
+            obstacle: Rgb(0x4a, 0x4a, 0x4a),
+            player: Rgb(0x2e, 0x8b, 0x57),
+            it: Rgb(0xd6, 0x28, 0x28),
+            frozen: Rgb(0x3a, 0x9a, 0xd9),
+            tag: Rgb(0xf5, 0xb7, 0x00)
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    /// Parses a comma separated list of `name=#rrggbb` pairs, for example `it=#ff0000,tag=#ffff00`.
+    /// Colours that are not listed keep their default.
+    fn from_str(colors: &str) -> Result<Self, Self::Err> {
+        let mut palette = Palette::default();
+        for pair in colors.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (name, hex) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("invalid colour: {}. Must be written as name=#rrggbb", pair))?;
+            let color = hex.trim().parse::<Rgb>()?;
+            match name.trim() {
+                "background" => palette.background = color,
+                "obstacle" => palette.obstacle = color,
+                "player" => palette.player = color,
+                "it" => palette.it = color,
+                "frozen" => palette.frozen = color,
+                "tag" => palette.tag = color,
+                name => {
+                    return Err(format!(
+                        "unknown colour name: {}. Must be one of: {}",
+                        name,
+                        Palette::NAMES.join(", ")
+                    ))
+                }
+            }
+        }
+
+        Ok(palette)
+    }
+}
+
+const BACKGROUND: u8 = 0;
+const OBSTACLE: u8 = 1;
+const PLAYER: u8 = 2;
+const IT: u8 = 3;
+const FROZEN: u8 = 4;
+const TAG: u8 = 5;
+
+/// Draws the field of play as images, with each position drawn as a square `cell_size` pixels wide.
+/// This is the image counterpart of [render_field](crate::renderer::render_field).
+#[derive(Clone, Copy, Debug)]
+pub struct ImageRenderer {
+    pub cell_size: u32,
+    pub palette: Palette
+}
+
+impl Default for ImageRenderer {
+    fn default() -> Self {
+        ImageRenderer {
+            cell_size: 8,
+            palette: Palette::default()
+        }
+    }
+}
+
+impl ImageRenderer {
+    /// The width and height in pixels of an image of `field_of_play`.
+    pub fn image_size(&self, field_of_play: &FieldOfPlay) -> (u32, u32) {
+        let x_len = field_of_play.x_len() as u32;
+        let y_len = field_of_play.y_len() as u32;
+
+        (x_len * self.cell_size, y_len * self.cell_size)
+    }
+
+    /// Returns the field as an SVG document. `tagged_players` are the indices of players who were
+    /// tagged on this turn, who are outlined in the tag colour.
+    pub fn render_svg(
+        &self,
+        field_of_play: &FieldOfPlay,
+        players: &[Player],
+        turn_num: usize,
+        tagged_players: &[usize]
+    ) -> String {
+        let (width, height) = self.image_size(field_of_play);
+        let colors = self.palette.colors();
+        let mut svg = String::new();
+        // Writing to a String can not fail.
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            width, height, width, height
+        );
+        let _ = writeln!(svg, "<title>Turn {}</title>", turn_num);
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="{}"/>"#,
+            width,
+            height,
+            colors[BACKGROUND as usize].to_hex()
+        );
+        for y in 0..field_of_play.y_len() {
+            for x in 0..field_of_play.x_len() {
+                let (fill, is_tagged) = cell_color(field_of_play, players, tagged_players, x, y);
+                if fill == BACKGROUND {
+                    continue;
+                }
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}""#,
+                    x as u32 * self.cell_size,
+                    y as u32 * self.cell_size,
+                    self.cell_size,
+                    self.cell_size,
+                    colors[fill as usize].to_hex()
+                );
+                if is_tagged {
+                    let _ = write!(
+                        svg,
+                        r#" stroke="{}" stroke-width="{}""#,
+                        colors[TAG as usize].to_hex(),
+                        self.tag_outline_width()
+                    );
+                }
+                let _ = writeln!(svg, "/>");
+            }
+        }
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    /// Writes the field as a PNG image. See [ImageRenderer::render_svg].
+    pub fn write_png<W: Write>(
+        &self,
+        writer: W,
+        field_of_play: &FieldOfPlay,
+        players: &[Player],
+        tagged_players: &[usize]
+    ) -> Result<(), String> {
+        let (width, height) = self.image_size(field_of_play);
+        let pixels = self.render_indexed(field_of_play, players, tagged_players);
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(self.palette_bytes());
+        let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+
+        writer.write_image_data(&pixels).map_err(|err| err.to_string())
+    }
+
+    /// Writes `heatmap` as a PNG image, with obstacles on `field_of_play` drawn in the obstacle colour.
+    /// See [Palette::heat_color] for how counts are coloured.
+    pub fn write_heatmap_png<W: Write>(
+        &self,
+        writer: W,
+        heatmap: &Heatmap,
+        field_of_play: &FieldOfPlay
+    ) -> Result<(), String> {
+        let (width, height) = self.image_size(field_of_play);
+        let cell_size = self.cell_size as usize;
+        let max = heatmap.max();
+        let mut pixels = vec![0; width as usize * height as usize * 3];
+        for y in 0..heatmap.y_len() {
+            for x in 0..heatmap.x_len() {
+                let color = if field_of_play.is_passable(x, y) {
+                    self.palette.heat_color(heatmap.get(x, y), max)
+                } else {
+                    self.palette.obstacle
+                };
+                for cell_y in 0..cell_size {
+                    let row_start = ((y * cell_size + cell_y) * width as usize + x * cell_size) * 3;
+                    for pixel in pixels[row_start..row_start + cell_size * 3].chunks_mut(3) {
+                        pixel.copy_from_slice(&[color.0, color.1, color.2]);
+                    }
+                }
+            }
+        }
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+
+        writer.write_image_data(&pixels).map_err(|err| err.to_string())
+    }
+
+    // One byte per pixel, each the index of its colour in the palette.
+    fn render_indexed(&self, field_of_play: &FieldOfPlay, players: &[Player], tagged_players: &[usize]) -> Vec<u8> {
+        let (width, height) = self.image_size(field_of_play);
+        let cell_size = self.cell_size as usize;
+        let outline_width = self.tag_outline_width() as usize;
+        let mut pixels = vec![BACKGROUND; width as usize * height as usize];
+        for y in 0..field_of_play.y_len() {
+            for x in 0..field_of_play.x_len() {
+                let (fill, is_tagged) = cell_color(field_of_play, players, tagged_players, x, y);
+                for cell_y in 0..cell_size {
+                    for cell_x in 0..cell_size {
+                        let is_outline = cell_x < outline_width
+                            || cell_y < outline_width
+                            || cell_x >= cell_size - outline_width
+                            || cell_y >= cell_size - outline_width;
+                        let pixel = (y * cell_size + cell_y) * width as usize + x * cell_size + cell_x;
+                        pixels[pixel] = if is_tagged && is_outline { TAG } else { fill };
+                    }
+                }
+            }
+        }
+
+        pixels
+    }
+
+    fn palette_bytes(&self) -> Vec<u8> {
+        self.palette
+            .colors()
+            .iter()
+            .flat_map(|color| vec![color.0, color.1, color.2])
+            .collect()
+    }
+
+    fn tag_outline_width(&self) -> u32 {
+        (self.cell_size / 5).max(1)
+    }
+}
+
+// The palette index of the colour to fill a position with and whether it holds a player who was just
+// tagged.
+fn cell_color(
+    field_of_play: &FieldOfPlay,
+    players: &[Player],
+    tagged_players: &[usize],
+    x: usize,
+    y: usize
+) -> (u8, bool) {
+    if !field_of_play.is_passable(x, y) {
+        return (OBSTACLE, false);
+    }
+
+    match field_of_play.get_occupant(x, y) {
+        Some(index) => {
+            let fill = if players[index].is_it {
+                IT
+            } else if players[index].is_frozen {
+                FROZEN
+            } else {
+                PLAYER
+            };
+            (fill, tagged_players.contains(&index))
+        }
+        None => (BACKGROUND, false)
+    }
+}
+
+/// Builds an animated GIF of a game one turn at a time.
+pub struct GifRecorder<W: Write> {
+    encoder: gif::Encoder<W>,
+    renderer: ImageRenderer,
+    width: u16,
+    height: u16,
+    // In hundredths of a second, which is what GIFs use.
+    frame_delay: u16
+}
+
+impl<W: Write> GifRecorder<W> {
+    /// Starts a GIF that loops forever, showing each frame for `frame_delay_ms`. Every frame must be
+    /// of a field the same size as `field_of_play`.
+    pub fn new(
+        writer: W,
+        renderer: ImageRenderer,
+        field_of_play: &FieldOfPlay,
+        frame_delay_ms: u64
+    ) -> Result<Self, String> {
+        let (width, height) = renderer.image_size(field_of_play);
+        let too_large_err = || format!("a {} by {} image is too large for a GIF", width, height);
+        let width = u16::try_from(width).map_err(|_| too_large_err())?;
+        let height = u16::try_from(height).map_err(|_| too_large_err())?;
+        let mut encoder =
+            gif::Encoder::new(writer, width, height, &renderer.palette_bytes()).map_err(|err| err.to_string())?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|err| err.to_string())?;
+
+        Ok(GifRecorder {
+            encoder,
+            renderer,
+            width,
+            height,
+            frame_delay: u16::try_from(frame_delay_ms / 10).unwrap_or(u16::MAX)
+        })
+    }
+
+    /// Adds the field as the next frame. See [ImageRenderer::render_svg].
+    pub fn add_frame(
+        &mut self,
+        field_of_play: &FieldOfPlay,
+        players: &[Player],
+        tagged_players: &[usize]
+    ) -> Result<(), String> {
+        let pixels = self.renderer.render_indexed(field_of_play, players, tagged_players);
+        let mut frame = gif::Frame::from_indexed_pixels(self.width, self.height, pixels, None);
+        frame.delay = self.frame_delay;
+
+        self.encoder.write_frame(&frame).map_err(|err| err.to_string())
+    }
+
+    /// Finishes the GIF and returns the writer.
+    pub fn finish(self) -> Result<W, String> {
+        self.encoder.into_inner().map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+use crate::models::rng::new_rng;
+
+#[cfg(test)]
+fn test_players(field_of_play: &mut FieldOfPlay) -> Vec<Player> {
+    let mut rng = new_rng(0);
+    (0..3)
+        .map(|index| Player::new(index, index == 0, field_of_play, &mut rng))
+        .collect()
+}
+
+#[test]
+fn palette_from_str_test() {
+    assert_eq!("#0a0B0c".parse::<Rgb>(), Ok(Rgb(10, 11, 12)));
+    assert!("0a0b0c".parse::<Rgb>().is_err());
+    assert!("#0a0b0".parse::<Rgb>().is_err());
+    assert!("#gg0b0c".parse::<Rgb>().is_err());
+
+    let palette = "it=#ff0000, tag=#00ff00".parse::<Palette>().unwrap();
+    assert_eq!(palette.it, Rgb(255, 0, 0));
+    assert_eq!(palette.tag, Rgb(0, 255, 0));
+    assert_eq!(palette.player, Palette::default().player);
+    assert!("sky=#ffffff".parse::<Palette>().is_err());
+}
+
+#[test]
+fn render_svg_test() {
+    let mut field_of_play = FieldOfPlay::new(4, 3);
+    let players = test_players(&mut field_of_play);
+    let renderer = ImageRenderer {
+        cell_size: 10,
+        ..ImageRenderer::default()
+    };
+
+    let svg = renderer.render_svg(&field_of_play, &players, 7, &[1]);
+
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="30""#));
+    assert!(svg.contains("<title>Turn 7</title>"));
+    // The background and one square per player, one of which is outlined.
+    assert_eq!(svg.matches("<rect").count(), 4);
+    assert_eq!(svg.matches("stroke=").count(), 1);
+    assert!(svg.contains(&Palette::default().it.to_hex()));
+}
+
+#[test]
+fn render_png_and_gif_test() {
+    let mut field_of_play = FieldOfPlay::new(4, 3);
+    let players = test_players(&mut field_of_play);
+    let renderer = ImageRenderer::default();
+
+    let pixels = renderer.render_indexed(&field_of_play, &players, &[1]);
+    assert_eq!(pixels.len(), 32 * 24);
+    let (x, y) = players[1].get_location();
+    let outline_pixel = y * 8 * 32 + x * 8;
+    assert_eq!(pixels[outline_pixel], TAG);
+    assert_eq!(pixels[outline_pixel + 4 * 32 + 4], PLAYER);
+
+    let mut png = Vec::new();
+    renderer.write_png(&mut png, &field_of_play, &players, &[]).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    let mut recorder = GifRecorder::new(Vec::new(), renderer, &field_of_play, 100).unwrap();
+    recorder.add_frame(&field_of_play, &players, &[]).unwrap();
+    recorder.add_frame(&field_of_play, &players, &[1]).unwrap();
+    let gif = recorder.finish().unwrap();
+    assert!(gif.starts_with(b"GIF89a"));
+}
+
+#[test]
+fn heat_color_test() {
+    let palette = Palette::default();
+    assert_eq!(palette.heat_color(0, 0), palette.background);
+    assert_eq!(palette.heat_color(0, 4), palette.background);
+    assert_eq!(palette.heat_color(2, 4), palette.tag);
+    assert_eq!(palette.heat_color(4, 4), palette.it);
+
+    let mut heatmap = Heatmap::new(4, 3);
+    heatmap.increment(1, 1);
+    let mut png = Vec::new();
+    ImageRenderer::default()
+        .write_heatmap_png(&mut png, &heatmap, &FieldOfPlay::new(4, 3))
+        .unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+}
@@ -3,7 +3,11 @@ pub struct Action {
     pub action: ActionType,
     pub x_coordinate: Option<usize>,
     pub y_coordinate: Option<usize>,
-    pub new_it_index: Option<usize>
+    /// Moves only. The positions passed through on the way to the x and y coordinates, in order.
+    /// Empty for a move to an adjacent position.
+    pub via: Vec<(usize, usize)>,
+    pub new_it_index: Option<usize>,
+    pub rescued_index: Option<usize>
 }
 
 impl Action {
@@ -12,16 +16,52 @@ impl Action {
             action: ActionType::Move,
             x_coordinate: Some(x),
             y_coordinate: Some(y),
-            new_it_index: None
+            via: Vec::new(),
+            new_it_index: None,
+            rescued_index: None
         }
     }
 
+    /// A move through every position in `path`, in order, ending on the last one. Panics if `path`
+    /// is empty.
+    pub fn new_path(path: &[(usize, usize)]) -> Self {
+        let ((x, y), via) = path.split_last().expect("A path must have at least one position.");
+
+        Self {
+            via: via.to_vec(),
+            ..Action::new_move(*x, *y)
+        }
+    }
+
+    /// Moves only. Every position the move passes through, ending where the move ends.
+    pub fn path(&self) -> Vec<(usize, usize)> {
+        let mut path = self.via.clone();
+        if let (Some(x), Some(y)) = (self.x_coordinate, self.y_coordinate) {
+            path.push((x, y));
+        }
+
+        path
+    }
+
     pub fn new_tag(index: usize) -> Self {
         Self {
             action: ActionType::Tag,
             x_coordinate: None,
             y_coordinate: None,
-            new_it_index: Some(index)
+            via: Vec::new(),
+            new_it_index: Some(index),
+            rescued_index: None
+        }
+    }
+
+    pub fn new_rescue(index: usize) -> Self {
+        Self {
+            action: ActionType::Rescue,
+            x_coordinate: None,
+            y_coordinate: None,
+            via: Vec::new(),
+            new_it_index: None,
+            rescued_index: Some(index)
         }
     }
 }
@@ -29,7 +69,8 @@ impl Action {
 #[derive(Debug, Eq, PartialEq)]
 pub enum ActionType {
     Move,
-    Tag
+    Tag,
+    Rescue
 }
 
 #[test]
@@ -51,3 +92,25 @@ fn action_new_move_test() {
     assert_eq!(new_tag.x_coordinate, Some(1));
     assert_eq!(new_tag.y_coordinate, Some(2));
 }
+
+#[test]
+fn action_new_path_test() {
+    let new_path = Action::new_path(&[(1, 1), (2, 2), (3, 2)]);
+
+    assert_eq!(new_path.action, ActionType::Move);
+    assert_eq!(new_path.x_coordinate, Some(3));
+    assert_eq!(new_path.y_coordinate, Some(2));
+    assert_eq!(new_path.via, vec![(1, 1), (2, 2)]);
+    assert_eq!(new_path.path(), vec![(1, 1), (2, 2), (3, 2)]);
+    assert!(Action::new_move(1, 2).via.is_empty());
+}
+
+#[test]
+fn action_new_rescue_test() {
+    let new_rescue = Action::new_rescue(3);
+
+    assert_eq!(new_rescue.action, ActionType::Rescue);
+    assert_eq!(new_rescue.rescued_index, Some(3));
+    assert_eq!(new_rescue.new_it_index, None);
+    assert_eq!(new_rescue.x_coordinate, None);
+}
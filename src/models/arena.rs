@@ -0,0 +1,179 @@
+use super::field_of_play::FieldOfPlay;
+use super::terrain::Terrain;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+const SPAWN_POINT_SYMBOL: char = 'S';
+const IT_START_SYMBOL: char = 'I';
+
+/// A field of play defined by a plain text map. Each line of the map is a row of the field and each
+/// character is a position:
+/// - `.` open ground
+/// - `#` wall, `T` tree, `~` water
+/// - `S` open ground where players who are not it may start
+/// - `I` open ground where it players may start
+///
+/// If a map has no `S` positions players who are not it can start anywhere open, and the same goes for
+/// `I` positions and it players.
+#[derive(Clone, Debug)]
+pub struct Arena {
+    terrain: Vec<Vec<Terrain>>,
+    spawn_points: Vec<(usize, usize)>,
+    it_start_points: Vec<(usize, usize)>
+}
+
+impl Arena {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let map = fs::read_to_string(path).map_err(|err| format!("could not read map {}: {}", path.display(), err))?;
+
+        map.parse::<Arena>()
+            .map_err(|err| format!("invalid map {}: {}", path.display(), err))
+    }
+
+    pub fn x_len(&self) -> usize {
+        self.terrain[0].len()
+    }
+
+    pub fn y_len(&self) -> usize {
+        self.terrain.len()
+    }
+
+    /// The positions players who are not it may start on. Empty if they can start anywhere open.
+    pub fn spawn_points(&self) -> &[(usize, usize)] {
+        &self.spawn_points
+    }
+
+    /// The positions it players may start on. Empty if they can start anywhere open.
+    pub fn it_start_points(&self) -> &[(usize, usize)] {
+        &self.it_start_points
+    }
+
+    /// Returns an error if the players can not all be placed on the arena.
+    pub fn validate_player_count(&self, num_players: usize, num_it_players: usize) -> Result<(), String> {
+        let open_positions = self
+            .terrain
+            .iter()
+            .flatten()
+            .filter(|terrain| terrain.is_passable())
+            .count();
+        if open_positions < num_players {
+            return Err(format!(
+                "{} players cannot fit on a map with {} open positions",
+                num_players, open_positions
+            ));
+        }
+        if !self.it_start_points.is_empty() && self.it_start_points.len() < num_it_players {
+            return Err(format!(
+                "{} it players cannot fit on a map with {} it start positions",
+                num_it_players,
+                self.it_start_points.len()
+            ));
+        }
+        let num_runners = num_players - num_it_players;
+        if !self.spawn_points.is_empty() && self.spawn_points.len() < num_runners {
+            return Err(format!(
+                "{} players who are not it cannot fit on a map with {} spawn points",
+                num_runners,
+                self.spawn_points.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds an empty field of play with the terrain of this arena.
+    pub fn build_field_of_play(&self) -> FieldOfPlay {
+        FieldOfPlay::from_terrain(self.terrain.clone())
+    }
+}
+
+impl FromStr for Arena {
+    type Err = String;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let mut terrain: Vec<Vec<Terrain>> = Vec::new();
+        let mut spawn_points = Vec::new();
+        let mut it_start_points = Vec::new();
+
+        // Blank lines at the end of the file are ignored, anywhere else they are an error.
+        let lines: Vec<&str> = map.trim_end().lines().map(|line| line.trim_end_matches('\r')).collect();
+        for (y, line) in lines.iter().enumerate() {
+            let mut y_axis = Vec::new();
+            for (x, symbol) in line.chars().enumerate() {
+                let position_terrain = match symbol {
+                    SPAWN_POINT_SYMBOL => {
+                        spawn_points.push((x, y));
+                        Terrain::Open
+                    }
+                    IT_START_SYMBOL => {
+                        it_start_points.push((x, y));
+                        Terrain::Open
+                    }
+                    _ => Terrain::from_symbol(symbol).ok_or_else(|| {
+                        format!(
+                            "line {}, column {}: unknown map symbol '{}'. Must be one of: . # T ~ S I",
+                            y + 1,
+                            x + 1,
+                            symbol
+                        )
+                    })?
+                };
+                y_axis.push(position_terrain);
+            }
+            if let Some(first_y_axis) = terrain.first() {
+                if y_axis.len() != first_y_axis.len() {
+                    return Err(format!(
+                        "line {}, column {}: every line must be {} columns long like line 1",
+                        y + 1,
+                        y_axis.len().min(first_y_axis.len()) + 1,
+                        first_y_axis.len()
+                    ));
+                }
+            } else if y_axis.is_empty() {
+                return Err("line 1, column 1: the first line of the map is empty".to_owned());
+            }
+            terrain.push(y_axis);
+        }
+        if terrain.is_empty() {
+            return Err("the map is empty".to_owned());
+        }
+
+        Ok(Arena {
+            terrain,
+            spawn_points,
+            it_start_points
+        })
+    }
+}
+
+#[test]
+fn arena_parse_test() {
+    let arena = "#####\n#S.I#\n#.T~#\n#####\n".parse::<Arena>().unwrap();
+    assert_eq!(arena.x_len(), 5);
+    assert_eq!(arena.y_len(), 4);
+    assert_eq!(arena.spawn_points(), &[(1, 1)]);
+    assert_eq!(arena.it_start_points(), &[(3, 1)]);
+
+    let field_of_play = arena.build_field_of_play();
+    assert_eq!(field_of_play.count_passable_positions(), 4);
+    assert_eq!(field_of_play.get_terrain(2, 2), Terrain::Tree);
+    assert!(field_of_play.is_passable(3, 1));
+
+    assert!(arena.validate_player_count(2, 1).is_ok());
+    assert!(arena.validate_player_count(3, 1).is_err());
+    assert!(arena.validate_player_count(3, 2).is_err());
+}
+
+#[test]
+fn arena_parse_error_test() {
+    assert_eq!(
+        "...\n.x.\n...".parse::<Arena>().unwrap_err(),
+        "line 2, column 2: unknown map symbol 'x'. Must be one of: . # T ~ S I"
+    );
+    assert_eq!(
+        "...\n..\n...".parse::<Arena>().unwrap_err(),
+        "line 2, column 3: every line must be 3 columns long like line 1"
+    );
+    assert_eq!("\n\n".parse::<Arena>().unwrap_err(), "the map is empty");
+}
@@ -1,5 +1,7 @@
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub enum Direction {
     East,
+    #[default]
     North,
     NorthEast,
     NorthWest,
@@ -8,3 +10,48 @@ pub enum Direction {
     SouthWest,
     West
 }
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::East,
+        Direction::North,
+        Direction::NorthEast,
+        Direction::NorthWest,
+        Direction::South,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West
+    ];
+
+    /// The change in (x, y) when moving one position in this direction. North is towards y = 0.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::East => (1, 0),
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::NorthWest => (-1, -1),
+            Direction::South => (0, 1),
+            Direction::SouthEast => (1, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0)
+        }
+    }
+
+    /// The direction of a single step with the given change in (x, y), or None if it is not a step
+    /// to an adjacent position.
+    pub fn from_offset(offset: (isize, isize)) -> Option<Direction> {
+        Direction::ALL
+            .iter()
+            .copied()
+            .find(|direction| direction.offset() == offset)
+    }
+}
+
+#[test]
+fn direction_offset_test() {
+    assert_eq!(Direction::North.offset(), (0, -1));
+    assert_eq!(Direction::SouthWest.offset(), (-1, 1));
+    assert!(Direction::ALL.iter().all(|direction| direction.offset() != (0, 0)));
+    assert_eq!(Direction::from_offset((1, -1)), Some(Direction::NorthEast));
+    assert_eq!(Direction::from_offset((0, 0)), None);
+}
@@ -0,0 +1,201 @@
+use super::resolution::MoveResolution;
+use super::rules::GameMode;
+use super::topology::Topology;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::mpsc::Sender;
+
+/// Something that happened during a game. Events are emitted in the order they happen, so the event
+/// log of a game is enough to follow it turn by turn without re-running the players. Players are
+/// identified by their index and positions by their (x, y) coordinates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Always the first event. Describes the field of play and where everyone starts.
+    GameStarted {
+        seed: u64,
+        game_mode: GameMode,
+        topology: Topology,
+        /// Logs written before this was recorded are replayed as sequential.
+        #[serde(default)]
+        move_resolution: MoveResolution,
+        num_turns: usize,
+        x_len: usize,
+        y_len: usize,
+        /// One string per row of the field using the map symbols for terrain. See
+        /// [Terrain::symbol](super::terrain::Terrain::symbol).
+        terrain: Vec<String>,
+        players: Vec<PlayerStart>
+    },
+    TurnStarted {
+        turn: usize
+    },
+    /// `player` moved from (from_x, from_y) to (to_x, to_y), passing through every position in
+    /// `via` on the way. `via` is empty, and left out of the JSON, for a move to an adjacent
+    /// position.
+    Moved {
+        turn: usize,
+        player: usize,
+        from_x: usize,
+        from_y: usize,
+        to_x: usize,
+        to_y: usize,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        via: Vec<(usize, usize)>
+    },
+    /// A player who was free to act ended their move where they started, either by choice, because
+    /// there was nowhere for them to go or because a collision with another player stopped them.
+    Stuck {
+        turn: usize,
+        player: usize,
+        x: usize,
+        y: usize
+    },
+    /// `player` at (x, y) tagged `tagged_player` at (tagged_x, tagged_y). In freeze tag the tagged
+    /// player is frozen, otherwise they become it.
+    Tagged {
+        turn: usize,
+        player: usize,
+        x: usize,
+        y: usize,
+        tagged_player: usize,
+        tagged_x: usize,
+        tagged_y: usize
+    },
+    /// Freeze tag only. `player` at (x, y) unfroze `rescued_player` at (rescued_x, rescued_y).
+    Rescued {
+        turn: usize,
+        player: usize,
+        x: usize,
+        y: usize,
+        rescued_player: usize,
+        rescued_x: usize,
+        rescued_y: usize
+    },
+    /// Always the last event.
+    GameEnded {
+        turn: usize,
+        reason: GameEndReason
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStart {
+    pub player: usize,
+    pub x: usize,
+    pub y: usize,
+    pub is_it: bool
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEndReason {
+    TurnLimit,
+    AllFrozen,
+    AllInfected
+}
+
+/// Receives every event emitted by a [Simulation](crate::Simulation).
+pub trait EventSink: Send {
+    fn record(&mut self, event: &Event);
+}
+
+/// Sends every event down a channel, so events can be read while the game is played, for example
+/// from another thread. Events sent after the receiver is dropped are discarded.
+impl EventSink for Sender<Event> {
+    fn record(&mut self, event: &Event) {
+        let _ = self.send(event.clone());
+    }
+}
+
+/// Writes every event as a line of JSON. The writer is flushed when the game ends. A failed write is
+/// logged and the rest of the game is still played, but nothing else is written.
+pub struct JsonLinesSink<W: Write + Send> {
+    writer: W,
+    has_failed: bool
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesSink {
+            writer,
+            has_failed: false
+        }
+    }
+
+    fn write_event(&mut self, event: &Event) -> Result<(), String> {
+        serde_json::to_writer(&mut self.writer, event).map_err(|err| err.to_string())?;
+        self.writer.write_all(b"\n").map_err(|err| err.to_string())?;
+        if let Event::GameEnded { .. } = event {
+            self.writer.flush().map_err(|err| err.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLinesSink<W> {
+    fn record(&mut self, event: &Event) {
+        if self.has_failed {
+            return;
+        }
+        if let Err(err) = self.write_event(event) {
+            error!(
+                "Could not write to the event log, no more events will be written: {}",
+                err
+            );
+            self.has_failed = true;
+        }
+    }
+}
+
+#[test]
+fn json_lines_sink_test() {
+    let mut sink = JsonLinesSink::new(Vec::new());
+    sink.record(&Event::TurnStarted { turn: 1 });
+    sink.record(&Event::Moved {
+        turn: 1,
+        player: 2,
+        from_x: 0,
+        from_y: 1,
+        to_x: 1,
+        to_y: 1,
+        via: Vec::new()
+    });
+    sink.record(&Event::Moved {
+        turn: 1,
+        player: 3,
+        from_x: 0,
+        from_y: 2,
+        to_x: 2,
+        to_y: 3,
+        via: vec![(1, 3)]
+    });
+    sink.record(&Event::GameEnded {
+        turn: 1,
+        reason: GameEndReason::TurnLimit
+    });
+
+    let written = String::from_utf8(sink.writer).unwrap();
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], r#"{"event":"turn_started","turn":1}"#);
+    assert_eq!(
+        serde_json::from_str::<Event>(lines[1]).unwrap(),
+        Event::Moved {
+            turn: 1,
+            player: 2,
+            from_x: 0,
+            from_y: 1,
+            to_x: 1,
+            to_y: 1,
+            via: Vec::new()
+        }
+    );
+    assert_eq!(
+        lines[2],
+        r#"{"event":"moved","turn":1,"player":3,"from_x":0,"from_y":2,"to_x":2,"to_y":3,"via":[[1,3]]}"#
+    );
+    assert_eq!(lines[3], r#"{"event":"game_ended","turn":1,"reason":"turn_limit"}"#);
+}
@@ -1,15 +1,30 @@
 use super::direction::Direction;
+use super::rng::SimRng;
+use super::terrain::Terrain;
+use super::topology::{axis_distance, axis_offset, offset_coordinate, Topology};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 
-type Field = Vec<Vec<Option<usize>>>;
+type TerrainMap = Vec<Vec<Terrain>>;
 
-// It shouldn't be possible to ever see this message since we should be correctly checking bounds.
-// We could probably get away with just using uwnrap.
-static GENERIC_VEC_ACCESS_PANIC_ERR_MSG: &str = "Invalid field index.";
+// Marks an empty position in `occupants`.
+const EMPTY: u32 = u32::MAX;
 
 #[derive(Debug)]
 pub struct FieldOfPlay {
-    pub field: Field,
-    last_known_it_coordinates: Option<(usize, usize)>
+    x_len: usize,
+    y_len: usize,
+    // The index of the player on every position, row by row so (x, y) is at y * x_len + x. Stored
+    // as u32 rather than Option<usize> so very large fields take a quarter of the memory.
+    occupants: Vec<u32>,
+    // Kept separate from `occupants`, which only tracks where players are. Indexed the same way.
+    terrain: Vec<Terrain>,
+    topology: Topology,
+    last_known_it_coordinates: Option<(usize, usize)>,
+    it_player_indices: HashSet<usize>,
+    frozen_player_indices: HashSet<usize>
 }
 
 #[derive(Default)]
@@ -25,199 +40,268 @@ impl PositionDetails {
 }
 
 impl FieldOfPlay {
-    pub fn new(x_len: usize, y_len: usize) -> Self {
-        let mut field_of_play = Vec::new();
-        (0..y_len).for_each(|_| {
-            let x_vec: Vec<Option<usize>> = (0..x_len).map(|_| None).collect();
-            field_of_play.push(x_vec);
-        });
+    /// The most players a field of play can hold.
+    pub const MAX_PLAYERS: usize = EMPTY as usize;
 
+    pub fn new(x_len: usize, y_len: usize) -> Self {
         FieldOfPlay {
-            field: field_of_play,
-            last_known_it_coordinates: None
+            x_len,
+            y_len,
+            occupants: vec![EMPTY; x_len * y_len],
+            terrain: vec![Terrain::Open; x_len * y_len],
+            topology: Topology::default(),
+            last_known_it_coordinates: None,
+            it_player_indices: HashSet::new(),
+            frozen_player_indices: HashSet::new()
         }
     }
 
-    /// Returns a vec of player indices that are adjacent to the input coordinates. This can be
-    /// used by a player who is it to find players to tag. It is up to the caller to determine if
-    /// the players are taggable and if there are multiple players, which one to tag it.
-    pub fn get_adjacent_player_indices(&self, it_x: usize, it_y: usize) -> Vec<usize> {
-        let mut player_indicies = Vec::new();
+    /// Builds an empty field of play with the given terrain. Every row must be the same length.
+    pub fn from_terrain(terrain: TerrainMap) -> Self {
+        let y_len = terrain.len();
+        let x_len = terrain.first().map_or(0, |y_axis| y_axis.len());
+        let mut field_of_play = FieldOfPlay::new(x_len, y_len);
+        field_of_play.terrain = terrain.into_iter().flatten().collect();
 
-        // NORTH
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_north(it_x, it_y).occupant {
-            player_indicies.push(player_index);
-        }
-        // SOUTH
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_south(it_x, it_y).occupant {
-            player_indicies.push(player_index);
-        }
-        // EAST
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_east(it_x, it_y).occupant {
-            player_indicies.push(player_index);
-        }
-        // WEST
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_west(it_x, it_y).occupant {
-            player_indicies.push(player_index);
-        }
-        // NORTHWEST
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_north_west(it_x, it_y).occupant {
-            player_indicies.push(player_index);
-        }
-        // NORTHEAST
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_north_east(it_x, it_y).occupant {
-            player_indicies.push(player_index);
-        }
-        // SOUTHWEST
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_south_west(it_x, it_y).occupant {
-            player_indicies.push(player_index);
+        field_of_play
+    }
+
+    /// Randomly turns open positions into obstacles. Each open position becomes an obstacle with a
+    /// probability of `density`, so 0.0 leaves the field open and 1.0 fills it. Occupied positions
+    /// are left alone.
+    pub fn generate_obstacles(&mut self, density: f64, rng: &mut SimRng) {
+        for (terrain, occupant) in self.terrain.iter_mut().zip(&self.occupants) {
+            if *occupant == EMPTY && terrain.is_passable() && rng.gen_bool(density) {
+                *terrain = *Terrain::OBSTACLES
+                    .choose(rng)
+                    .expect("There is always at least one obstacle type.");
+            }
         }
-        // SOUTHEAST
-        if let Some(player_index) = self.is_position_valid_and_get_occupant_south_east(it_x, it_y).occupant {
-            player_indicies.push(player_index);
+    }
+
+    pub fn x_len(&self) -> usize {
+        self.x_len
+    }
+
+    pub fn y_len(&self) -> usize {
+        self.y_len
+    }
+
+    /// The index of the player at (x, y), or None if nobody is there.
+    pub fn get_occupant(&self, x: usize, y: usize) -> Option<usize> {
+        match self.occupants[self.position_index(x, y)] {
+            EMPTY => None,
+            occupant => Some(occupant as usize)
         }
+    }
+
+    /// Puts the player at `occupant` on (x, y), or empties it if None. Players must be less than
+    /// [FieldOfPlay::MAX_PLAYERS].
+    pub fn set_occupant(&mut self, x: usize, y: usize, occupant: Option<usize>) {
+        let position_index = self.position_index(x, y);
+        self.occupants[position_index] = occupant.map_or(EMPTY, |occupant| {
+            u32::try_from(occupant)
+                .ok()
+                .filter(|occupant| *occupant != EMPTY)
+                .expect("Player indices fit in the field of play.")
+        });
+    }
+
+    pub fn get_terrain(&self, x: usize, y: usize) -> Terrain {
+        self.terrain[self.position_index(x, y)]
+    }
 
-        player_indicies
+    pub fn set_terrain(&mut self, x: usize, y: usize, terrain: Terrain) {
+        let position_index = self.position_index(x, y);
+        self.terrain[position_index] = terrain;
+    }
+
+    /// Returns true if (x, y) is on the field and players can stand there.
+    pub fn is_passable(&self, x: usize, y: usize) -> bool {
+        x < self.x_len && y < self.y_len && self.get_terrain(x, y).is_passable()
+    }
+
+    /// The number of positions players can stand on, ignoring whether they are occupied.
+    pub fn count_passable_positions(&self) -> usize {
+        self.terrain.iter().filter(|terrain| terrain.is_passable()).count()
+    }
+
+    /// Returns a vec of player indices that are adjacent to the input coordinates. This can be
+    /// used by a player who is it to find players to tag. It is up to the caller to determine if
+    /// the players are taggable and if there are multiple players, which one to tag it.
+    pub fn get_adjacent_player_indices(&self, it_x: usize, it_y: usize) -> Vec<usize> {
+        // The order matters. When more than one player is adjacent the first one found is tagged.
+        [
+            Direction::North,
+            Direction::South,
+            Direction::East,
+            Direction::West,
+            Direction::NorthWest,
+            Direction::NorthEast,
+            Direction::SouthWest,
+            Direction::SouthEast
+        ]
+        .iter()
+        .filter_map(|direction| self.is_position_valid_and_get_occupant(*direction, it_x, it_y).occupant)
+        .collect()
     }
 
     /// Returns a bool that is true if the adjacent position for the provided coordinates in the
     /// provided direction is empty. The coordinates should be the current coordinates of a player,
     /// not the desired ones.
     pub fn is_position_valid_and_empty(&self, direction: Direction, x: usize, y: usize) -> bool {
-        match direction {
-            Direction::East => self.is_position_valid_and_get_occupant_east(x, y).can_move_to(),
-            Direction::North => self.is_position_valid_and_get_occupant_north(x, y).can_move_to(),
-            Direction::NorthEast => self.is_position_valid_and_get_occupant_north_east(x, y).can_move_to(),
-            Direction::NorthWest => self.is_position_valid_and_get_occupant_north_west(x, y).can_move_to(),
-            Direction::South => self.is_position_valid_and_get_occupant_south(x, y).can_move_to(),
-            Direction::SouthEast => self.is_position_valid_and_get_occupant_south_east(x, y).can_move_to(),
-            Direction::SouthWest => self.is_position_valid_and_get_occupant_south_west(x, y).can_move_to(),
-            Direction::West => self.is_position_valid_and_get_occupant_west(x, y).can_move_to()
-        }
+        self.is_position_valid_and_get_occupant(direction, x, y).can_move_to()
     }
 
-    pub fn set_last_known_it_location(&mut self, x: usize, y: usize) {
-        self.last_known_it_coordinates = Some((x, y));
+    /// Returns the coordinates of the position adjacent to (x, y) in the given direction, or None if
+    /// that position is off the field.
+    pub fn get_position_in_direction(&self, direction: Direction, x: usize, y: usize) -> Option<(usize, usize)> {
+        self.get_position_at_offset(x, y, direction.offset())
     }
 
-    pub fn get_last_known_it_location(&self) -> Option<(usize, usize)> {
-        self.last_known_it_coordinates
+    /// Returns the coordinates `offset` positions away from (x, y), wrapping around the edges the
+    /// topology joins. None if that position is off the field.
+    pub fn get_position_at_offset(
+        &self,
+        x: usize,
+        y: usize,
+        (x_offset, y_offset): (isize, isize)
+    ) -> Option<(usize, usize)> {
+        let new_x = offset_coordinate(x, x_offset, self.x_len, self.topology.wraps_x())?;
+        let new_y = offset_coordinate(y, y_offset, self.y_len, self.topology.wraps_y())?;
+
+        Some((new_x, new_y))
     }
 
-    fn is_position_valid_and_get_occupant_south(&self, x: usize, y: usize) -> PositionDetails {
-        if y < self.field.len() - 1 {
-            let y_axis = self
-                .field
-                .get((y + 1) as usize)
-                .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
-        }
+    /// The number of steps between two positions along each axis, taking the shorter way around
+    /// the edges the topology joins.
+    pub fn get_axis_distances(&self, from: (usize, usize), to: (usize, usize)) -> (usize, usize) {
+        (
+            axis_distance(from.0, to.0, self.x_len, self.topology.wraps_x()),
+            axis_distance(from.1, to.1, self.y_len, self.topology.wraps_y())
+        )
+    }
 
-        PositionDetails::default()
+    /// The (x, y) offset that takes a player from `from` to `to` the shortest way, taking the shorter
+    /// way around the edges the topology joins.
+    pub fn get_offset(&self, from: (usize, usize), to: (usize, usize)) -> (isize, isize) {
+        (
+            axis_offset(from.0, to.0, self.x_len, self.topology.wraps_x()),
+            axis_offset(from.1, to.1, self.y_len, self.topology.wraps_y())
+        )
     }
 
-    fn is_position_valid_and_get_occupant_north(&self, x: usize, y: usize) -> PositionDetails {
-        if y > 0 {
-            let y_axis = self
-                .field
-                .get((y - 1) as usize)
-                .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
-        }
+    /// Returns true if a player at `from` could move to `to` in a single step.
+    pub fn is_adjacent(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let (x_distance, y_distance) = self.get_axis_distances(from, to);
 
-        PositionDetails::default()
+        x_distance <= 1 && y_distance <= 1
     }
 
-    fn is_position_valid_and_get_occupant_east(&self, x: usize, y: usize) -> PositionDetails {
-        let y_axis = self.field.get(y).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-        if let Some(pos_x) = y_axis.get(x + 1) {
-            return PositionDetails {
-                is_valid: true,
-                occupant: *pos_x
-            };
-        }
+    /// Returns true if a player at `from` could move through every position in `path` in order.
+    /// Each position must be next to the one before it, empty and not an obstacle, and no position
+    /// can be visited twice. An empty path is never open.
+    pub fn is_open_path(&self, from: (usize, usize), path: &[(usize, usize)]) -> bool {
+        let mut visited = HashSet::new();
+        visited.insert(from);
+        let mut previous = from;
+
+        !path.is_empty()
+            && path.iter().all(|(x, y)| {
+                let is_open = self.is_adjacent(previous, (*x, *y))
+                    && visited.insert((*x, *y))
+                    && self.is_passable(*x, *y)
+                    && self.get_occupant(*x, *y).is_none();
+                previous = (*x, *y);
+                is_open
+            })
+    }
 
-        PositionDetails::default()
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
     }
 
-    fn is_position_valid_and_get_occupant_west(&self, x: usize, y: usize) -> PositionDetails {
-        if x > 0 {
-            let y_axis = self.field.get(y).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x - 1) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
-        }
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
 
-        PositionDetails::default()
+    /// Returns the index and coordinates of every player on the field. This walks the whole field
+    /// so callers that only care about nearby players should search around a position instead.
+    pub fn get_player_positions(&self) -> Vec<(usize, (usize, usize))> {
+        self.occupants
+            .iter()
+            .enumerate()
+            .filter(|(_, occupant)| **occupant != EMPTY)
+            .map(|(position_index, occupant)| {
+                (
+                    *occupant as usize,
+                    (position_index % self.x_len, position_index / self.x_len)
+                )
+            })
+            .collect()
     }
 
-    fn is_position_valid_and_get_occupant_south_east(&self, x: usize, y: usize) -> PositionDetails {
-        if y < self.field.len() - 1 {
-            let y_axis = self.field.get(y + 1).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x + 1) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
-        }
+    pub fn set_last_known_it_location(&mut self, x: usize, y: usize) {
+        self.last_known_it_coordinates = Some((x, y));
+    }
 
-        PositionDetails::default()
+    pub fn get_last_known_it_location(&self) -> Option<(usize, usize)> {
+        self.last_known_it_coordinates
     }
 
-    fn is_position_valid_and_get_occupant_south_west(&self, x: usize, y: usize) -> PositionDetails {
-        if y < self.field.len() - 1 && x > 0 {
-            let y_axis = self.field.get(y + 1).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x - 1) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
+    /// Records whether the player at `index` is it. There can be more than one it player at a time.
+    pub fn set_it(&mut self, index: usize, is_it: bool) {
+        if is_it {
+            self.it_player_indices.insert(index);
+        } else {
+            self.it_player_indices.remove(&index);
         }
+    }
 
-        PositionDetails::default()
+    pub fn is_it(&self, index: usize) -> bool {
+        self.it_player_indices.contains(&index)
     }
 
-    fn is_position_valid_and_get_occupant_north_west(&self, x: usize, y: usize) -> PositionDetails {
-        if y > 0 && x > 0 {
-            let y_axis = self.field.get(y - 1).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x - 1) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
+    /// The index of every it player, in no particular order.
+    pub fn it_player_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.it_player_indices.iter().copied()
+    }
+
+    /// Records whether the player at `index` is frozen so other players can tell without having
+    /// access to every [Player](super::player::Player).
+    pub fn set_frozen(&mut self, index: usize, is_frozen: bool) {
+        if is_frozen {
+            self.frozen_player_indices.insert(index);
+        } else {
+            self.frozen_player_indices.remove(&index);
         }
+    }
 
-        PositionDetails::default()
+    pub fn is_frozen(&self, index: usize) -> bool {
+        self.frozen_player_indices.contains(&index)
     }
 
-    fn is_position_valid_and_get_occupant_north_east(&self, x: usize, y: usize) -> PositionDetails {
-        if y > 0 {
-            let y_axis = self.field.get(y - 1).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-            if let Some(pos_x) = y_axis.get(x + 1) {
-                return PositionDetails {
-                    is_valid: true,
-                    occupant: *pos_x
-                };
-            }
+    // The details of the position next to (x, y) in the given direction. Positions off the field are
+    // invalid and empty.
+    fn is_position_valid_and_get_occupant(&self, direction: Direction, x: usize, y: usize) -> PositionDetails {
+        match self.get_position_in_direction(direction, x, y) {
+            Some((new_x, new_y)) => PositionDetails {
+                is_valid: self.is_passable(new_x, new_y),
+                occupant: self.get_occupant(new_x, new_y)
+            },
+            None => PositionDetails::default()
         }
+    }
+
+    fn position_index(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.x_len && y < self.y_len,
+            "({}, {}) is off the field of play.",
+            x,
+            y
+        );
 
-        PositionDetails::default()
+        y * self.x_len + x
     }
 }
 #[test]
@@ -242,28 +326,29 @@ fn position_can_move_to_test() {
 
 #[test]
 fn field_of_play_new_test() {
-    let field_of_play = FieldOfPlay::new(3, 3);
+    let mut field_of_play = FieldOfPlay::new(3, 3);
 
-    assert_eq!(field_of_play.field.len(), 3);
+    assert_eq!((field_of_play.x_len(), field_of_play.y_len()), (3, 3));
+    assert!(field_of_play.get_player_positions().is_empty());
 
-    field_of_play.field.iter().for_each(|y_row| {
-        assert_eq!(y_row.len(), 3);
-        assert!(y_row.iter().all(|x| x.is_none()));
-    });
+    field_of_play.set_occupant(2, 1, Some(4));
+    assert_eq!(field_of_play.get_occupant(2, 1), Some(4));
+    field_of_play.set_occupant(2, 1, None);
+    assert_eq!(field_of_play.get_occupant(2, 1), None);
 }
 
 #[test]
 fn field_get_adjacent_player_indices_test() {
     let mut field_of_play = FieldOfPlay::new(3, 3);
 
-    field_of_play.field[0][0] = Some(1);
-    field_of_play.field[0][1] = Some(2);
-    field_of_play.field[0][2] = Some(3);
-    field_of_play.field[1][0] = Some(4);
-    field_of_play.field[1][2] = Some(5);
-    field_of_play.field[2][0] = Some(6);
-    field_of_play.field[2][1] = Some(7);
-    field_of_play.field[2][2] = Some(8);
+    field_of_play.set_occupant(0, 0, Some(1));
+    field_of_play.set_occupant(1, 0, Some(2));
+    field_of_play.set_occupant(2, 0, Some(3));
+    field_of_play.set_occupant(0, 1, Some(4));
+    field_of_play.set_occupant(2, 1, Some(5));
+    field_of_play.set_occupant(0, 2, Some(6));
+    field_of_play.set_occupant(1, 2, Some(7));
+    field_of_play.set_occupant(2, 2, Some(8));
 
     let adj_players = field_of_play.get_adjacent_player_indices(1, 1);
     assert_eq!(adj_players.len(), 8);
@@ -274,14 +359,14 @@ fn field_is_position_valid_and_empty_test() {
     let mut field_of_play = FieldOfPlay::new(3, 3);
 
     // First test that all positions are full and so all calls should return false
-    field_of_play.field[0][0] = Some(1);
-    field_of_play.field[0][1] = Some(2);
-    field_of_play.field[0][2] = Some(3);
-    field_of_play.field[1][0] = Some(4);
-    field_of_play.field[1][2] = Some(5);
-    field_of_play.field[2][0] = Some(6);
-    field_of_play.field[2][1] = Some(7);
-    field_of_play.field[2][2] = Some(8);
+    field_of_play.set_occupant(0, 0, Some(1));
+    field_of_play.set_occupant(1, 0, Some(2));
+    field_of_play.set_occupant(2, 0, Some(3));
+    field_of_play.set_occupant(0, 1, Some(4));
+    field_of_play.set_occupant(2, 1, Some(5));
+    field_of_play.set_occupant(0, 2, Some(6));
+    field_of_play.set_occupant(1, 2, Some(7));
+    field_of_play.set_occupant(2, 2, Some(8));
 
     assert!(!field_of_play.is_position_valid_and_empty(Direction::East, 1, 1));
     assert!(!field_of_play.is_position_valid_and_empty(Direction::West, 1, 1));
@@ -293,14 +378,14 @@ fn field_is_position_valid_and_empty_test() {
     assert!(!field_of_play.is_position_valid_and_empty(Direction::SouthWest, 1, 1));
 
     // Now test empty positions
-    field_of_play.field[0][0] = None;
-    field_of_play.field[0][1] = None;
-    field_of_play.field[0][2] = None;
-    field_of_play.field[1][0] = None;
-    field_of_play.field[1][2] = None;
-    field_of_play.field[2][0] = None;
-    field_of_play.field[2][1] = None;
-    field_of_play.field[2][2] = None;
+    field_of_play.set_occupant(0, 0, None);
+    field_of_play.set_occupant(1, 0, None);
+    field_of_play.set_occupant(2, 0, None);
+    field_of_play.set_occupant(0, 1, None);
+    field_of_play.set_occupant(2, 1, None);
+    field_of_play.set_occupant(0, 2, None);
+    field_of_play.set_occupant(1, 2, None);
+    field_of_play.set_occupant(2, 2, None);
 
     assert!(field_of_play.is_position_valid_and_empty(Direction::East, 1, 1));
     assert!(field_of_play.is_position_valid_and_empty(Direction::West, 1, 1));
@@ -319,3 +404,141 @@ fn field_last_known_it_position_test() {
     field_of_play.set_last_known_it_location(3, 4);
     assert_eq!(field_of_play.get_last_known_it_location(), Some((3, 4)));
 }
+
+#[test]
+fn field_get_position_in_direction_test() {
+    let field_of_play = FieldOfPlay::new(3, 3);
+
+    assert_eq!(
+        field_of_play.get_position_in_direction(Direction::NorthWest, 1, 1),
+        Some((0, 0))
+    );
+    assert_eq!(
+        field_of_play.get_position_in_direction(Direction::SouthEast, 1, 1),
+        Some((2, 2))
+    );
+    assert_eq!(field_of_play.get_position_in_direction(Direction::North, 1, 0), None);
+    assert_eq!(field_of_play.get_position_in_direction(Direction::East, 2, 1), None);
+}
+
+#[test]
+fn field_get_player_positions_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    field_of_play.set_occupant(2, 0, Some(1));
+    field_of_play.set_occupant(1, 2, Some(0));
+
+    assert_eq!(field_of_play.get_player_positions(), vec![(1, (2, 0)), (0, (1, 2))]);
+}
+
+#[test]
+fn field_frozen_players_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    assert!(!field_of_play.is_frozen(1));
+
+    field_of_play.set_frozen(1, true);
+    assert!(field_of_play.is_frozen(1));
+    assert!(!field_of_play.is_frozen(2));
+
+    field_of_play.set_frozen(1, false);
+    assert!(!field_of_play.is_frozen(1));
+}
+
+#[test]
+fn field_it_players_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    field_of_play.set_it(0, true);
+    field_of_play.set_it(2, true);
+    assert!(field_of_play.is_it(0));
+    assert!(!field_of_play.is_it(1));
+    assert!(field_of_play.is_it(2));
+
+    field_of_play.set_it(0, false);
+    assert!(!field_of_play.is_it(0));
+}
+
+#[test]
+fn field_obstacles_are_not_valid_positions_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    field_of_play.set_terrain(1, 0, Terrain::Wall);
+    field_of_play.set_terrain(2, 2, Terrain::Water);
+
+    assert!(!field_of_play.is_position_valid_and_empty(Direction::North, 1, 1));
+    assert!(!field_of_play.is_position_valid_and_empty(Direction::SouthEast, 1, 1));
+    assert!(field_of_play.is_position_valid_and_empty(Direction::South, 1, 1));
+    assert!(!field_of_play.is_passable(1, 0));
+    assert!(!field_of_play.is_passable(3, 0));
+    assert_eq!(field_of_play.get_terrain(2, 2), Terrain::Water);
+    assert_eq!(field_of_play.count_passable_positions(), 7);
+}
+
+#[test]
+fn field_is_open_path_test() {
+    let mut field_of_play = FieldOfPlay::new(4, 4);
+    field_of_play.set_terrain(2, 0, Terrain::Wall);
+    field_of_play.set_occupant(0, 0, Some(0));
+    field_of_play.set_occupant(3, 3, Some(1));
+
+    assert!(field_of_play.is_open_path((0, 0), &[(1, 1), (2, 2), (3, 2)]));
+    assert!(!field_of_play.is_open_path((0, 0), &[]));
+    // Skipping a position, through a wall, onto another player, back over the path or to the start.
+    assert!(!field_of_play.is_open_path((0, 0), &[(1, 1), (3, 1)]));
+    assert!(!field_of_play.is_open_path((0, 0), &[(1, 0), (2, 0)]));
+    assert!(!field_of_play.is_open_path((0, 0), &[(1, 1), (2, 2), (3, 3)]));
+    assert!(!field_of_play.is_open_path((0, 0), &[(1, 1), (2, 1), (1, 1)]));
+    assert!(!field_of_play.is_open_path((0, 0), &[(1, 1), (0, 0)]));
+}
+
+#[test]
+fn field_generate_obstacles_test() {
+    let mut rng = super::rng::new_rng(3);
+    let mut open_field = FieldOfPlay::new(10, 10);
+    open_field.generate_obstacles(0.0, &mut rng);
+    assert_eq!(open_field.count_passable_positions(), 100);
+
+    let mut full_field = FieldOfPlay::new(10, 10);
+    full_field.set_occupant(4, 4, Some(0));
+    full_field.generate_obstacles(1.0, &mut rng);
+    assert_eq!(full_field.count_passable_positions(), 1);
+    assert!(full_field.is_passable(4, 4));
+
+    let mut some_field = FieldOfPlay::new(10, 10);
+    some_field.generate_obstacles(0.3, &mut rng);
+    let passable = some_field.count_passable_positions();
+    assert!(passable > 0 && passable < 100);
+}
+
+#[test]
+fn field_from_terrain_test() {
+    let field_of_play = FieldOfPlay::from_terrain(vec![vec![Terrain::Open, Terrain::Tree]; 3]);
+
+    assert_eq!((field_of_play.x_len(), field_of_play.y_len()), (2, 3));
+    assert_eq!(field_of_play.get_terrain(1, 2), Terrain::Tree);
+}
+
+#[test]
+fn field_torus_topology_test() {
+    let mut field_of_play = FieldOfPlay::new(4, 3);
+    field_of_play.set_occupant(3, 2, Some(1));
+    assert!(field_of_play.get_adjacent_player_indices(0, 0).is_empty());
+    assert_eq!(
+        field_of_play.get_position_in_direction(Direction::NorthWest, 0, 0),
+        None
+    );
+
+    field_of_play.set_topology(Topology::Torus);
+    assert_eq!(field_of_play.get_adjacent_player_indices(0, 0), vec![1]);
+    assert_eq!(
+        field_of_play.get_position_in_direction(Direction::NorthWest, 0, 0),
+        Some((3, 2))
+    );
+    assert!(!field_of_play.is_position_valid_and_empty(Direction::NorthWest, 0, 0));
+    assert_eq!(field_of_play.get_axis_distances((0, 0), (3, 2)), (1, 1));
+
+    field_of_play.set_topology(Topology::WrapX);
+    assert_eq!(
+        field_of_play.get_position_in_direction(Direction::West, 0, 0),
+        Some((3, 0))
+    );
+    assert_eq!(field_of_play.get_position_in_direction(Direction::North, 0, 0), None);
+    assert_eq!(field_of_play.get_axis_distances((0, 0), (3, 2)), (1, 2));
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// The heatmaps recorded by [Stats](super::stats::Stats).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HeatmapKind {
+    /// How many turns each position ended with a player on it.
+    Occupancy,
+    /// How many turns each position ended with an it player on it.
+    It,
+    /// How many players were tagged on each position.
+    Tags
+}
+
+impl HeatmapKind {
+    pub const NAMES: [&'static str; 3] = ["occupancy", "it", "tags"];
+    pub const ALL: [HeatmapKind; 3] = [HeatmapKind::Occupancy, HeatmapKind::It, HeatmapKind::Tags];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapKind::Occupancy => "occupancy",
+            HeatmapKind::It => "it",
+            HeatmapKind::Tags => "tags"
+        }
+    }
+}
+
+impl FromStr for HeatmapKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        HeatmapKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown heatmap: {}. Must be one of: {}",
+                    name,
+                    HeatmapKind::NAMES.join(", ")
+                )
+            })
+    }
+}
+
+// Roughly how many times more memory a position takes in a sparse map than in a dense one.
+const SPARSE_OVERHEAD: usize = 6;
+
+/// A count for every position on the field of play, accumulated over a whole game.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    x_len: usize,
+    y_len: usize,
+    counts: Counts
+}
+
+// Positions are numbered row by row, so the count for (x, y) is at y * x_len + x. Counts are u32 to
+// keep the maps of very large fields small, which allows a little over four billion turns per
+// position.
+#[derive(Clone, Debug)]
+enum Counts {
+    // Only the positions counted so far. Maps like the tag heatmap stay this way on large fields.
+    Sparse(BTreeMap<usize, u32>),
+    // Every position, once enough have been counted that this takes less memory.
+    Dense(Vec<u32>)
+}
+
+impl Heatmap {
+    pub fn new(x_len: usize, y_len: usize) -> Self {
+        Heatmap {
+            x_len,
+            y_len,
+            counts: Counts::Sparse(BTreeMap::new())
+        }
+    }
+
+    pub fn increment(&mut self, x: usize, y: usize) {
+        let position_index = self.position_index(x, y);
+        let position_count = self.x_len * self.y_len;
+        match &mut self.counts {
+            Counts::Sparse(counts) => {
+                *counts.entry(position_index).or_insert(0) += 1;
+                if counts.len() * SPARSE_OVERHEAD > position_count {
+                    let mut dense = vec![0; position_count];
+                    counts
+                        .iter()
+                        .for_each(|(position_index, count)| dense[*position_index] = *count);
+                    self.counts = Counts::Dense(dense);
+                }
+            }
+            Counts::Dense(counts) => counts[position_index] += 1
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> usize {
+        let position_index = self.position_index(x, y);
+        match &self.counts {
+            Counts::Sparse(counts) => counts.get(&position_index).copied().unwrap_or(0) as usize,
+            Counts::Dense(counts) => counts[position_index] as usize
+        }
+    }
+
+    pub fn x_len(&self) -> usize {
+        self.x_len
+    }
+
+    pub fn y_len(&self) -> usize {
+        self.y_len
+    }
+
+    /// The highest count on the map, or 0 if nothing was recorded.
+    pub fn max(&self) -> usize {
+        self.counted().map(|(_, count)| count).max().unwrap_or(0) as usize
+    }
+
+    pub fn total(&self) -> usize {
+        self.counted().map(|(_, count)| count as usize).sum()
+    }
+
+    /// The counts as a matrix with one line per row of the field, top row first, and the counts of a
+    /// row separated by commas.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for y in 0..self.y_len {
+            let row: Vec<String> = (0..self.x_len).map(|x| self.get(x, y).to_string()).collect();
+            // Writing to a String can not fail.
+            let _ = writeln!(csv, "{}", row.join(","));
+        }
+
+        csv
+    }
+
+    // Every position with a count above 0 and its count, in position order.
+    fn counted(&self) -> Box<dyn Iterator<Item = (usize, u32)> + '_> {
+        match &self.counts {
+            Counts::Sparse(counts) => Box::new(counts.iter().map(|(position_index, count)| (*position_index, *count))),
+            Counts::Dense(counts) => Box::new(counts.iter().copied().enumerate().filter(|(_, count)| *count > 0))
+        }
+    }
+
+    fn position_index(&self, x: usize, y: usize) -> usize {
+        assert!(x < self.x_len && y < self.y_len, "({}, {}) is off the heatmap.", x, y);
+
+        y * self.x_len + x
+    }
+}
+
+// Two heatmaps with the same counts are equal whether or not they are stored the same way.
+impl PartialEq for Heatmap {
+    fn eq(&self, other: &Self) -> bool {
+        (self.x_len, self.y_len) == (other.x_len, other.y_len) && self.counted().eq(other.counted())
+    }
+}
+
+impl Eq for Heatmap {}
+
+#[test]
+fn heatmap_test() {
+    let mut heatmap = Heatmap::new(3, 2);
+    heatmap.increment(0, 0);
+    heatmap.increment(2, 1);
+    heatmap.increment(2, 1);
+
+    assert_eq!(heatmap.get(2, 1), 2);
+    assert_eq!(heatmap.max(), 2);
+    assert_eq!(heatmap.total(), 3);
+    assert_eq!(heatmap.to_csv(), "1,0,0\n0,0,2\n");
+
+    // Counting enough positions switches to storing every position, which changes nothing else.
+    let mut large = Heatmap::new(10, 10);
+    large.increment(3, 4);
+    let sparse = large.clone();
+    assert!(matches!(sparse.counts, Counts::Sparse(_)));
+    (0..10).for_each(|x| (0..2).for_each(|y| large.increment(x, y)));
+    assert!(matches!(large.counts, Counts::Dense(_)));
+    assert_eq!((large.get(3, 4), large.get(9, 1), large.get(9, 2)), (1, 1, 0));
+    assert_eq!((large.max(), large.total()), (1, 21));
+    let mut dense_counts = vec![0; 100];
+    dense_counts[43] = 1;
+    let dense = Heatmap {
+        counts: Counts::Dense(dense_counts),
+        ..Heatmap::new(10, 10)
+    };
+    assert_eq!(dense, sparse);
+    assert_ne!(dense, large);
+    assert_eq!("it".parse::<HeatmapKind>(), Ok(HeatmapKind::It));
+    assert!("corners".parse::<HeatmapKind>().is_err());
+}
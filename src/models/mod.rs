@@ -1,5 +1,18 @@
 pub mod action;
+pub mod arena;
 pub mod direction;
+pub mod event;
 pub mod field_of_play;
+pub mod heatmap;
 pub mod player;
+pub mod pursuit;
+pub mod resolution;
+pub mod rng;
+pub mod rules;
 pub mod stats;
+pub mod strategy;
+pub mod tag_network;
+pub mod terrain;
+pub mod topology;
+pub mod turn_order;
+pub mod vision;
@@ -1,276 +1,434 @@
-use super::action::Action;
+use super::action::{Action, ActionType};
 use super::direction::Direction;
 use super::field_of_play::FieldOfPlay;
-use log::{debug, error};
-use rand::{thread_rng, Rng};
+use super::rng::SimRng;
+use super::rules::GameMode;
+use super::strategy::{MovementStrategy, Strategies};
+use super::vision::Vision;
+use log::{debug, warn};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// The stamina a player gets back for each turn they stand still, up to their max stamina.
+pub const STAMINA_RECOVERY: usize = 2;
+/// Players who are not it only sprint when the it player was last seen at most this many positions
+/// away.
+pub const SPRINT_DISTANCE: usize = 5;
 
 #[derive(Clone, Default, Debug)]
 pub struct Player {
     pub is_it: bool,
+    pub is_frozen: bool,
     pub name: String,
+    index: usize,
     x_coordinate: usize,
     y_coordinate: usize,
-    risk_tolerance: f64
+    risk_tolerance: f64,
+    // The most positions the player can move in a turn. Every position after the first costs a
+    // stamina.
+    max_speed: usize,
+    max_stamina: usize,
+    stamina: usize,
+    // The direction of the last step the player took.
+    facing: Direction,
+    // Players without vision always know where the it player was last seen.
+    vision: Option<Vision>,
+    last_known_it_location: Option<(usize, usize)>,
+    // The index of the player who last tagged this player.
+    tagged_by: Option<usize>,
+    // No tag-backs. Maps the index of a player who tagged this player to the first turn on which this
+    // player may tag them back.
+    tag_back_blocked_until: HashMap<usize, usize>
 }
 
 impl Player {
-    pub fn new(index: usize, is_it: bool, field_of_play: &mut FieldOfPlay) -> Self {
+    pub fn new(index: usize, is_it: bool, field_of_play: &mut FieldOfPlay, rng: &mut SimRng) -> Self {
+        Player::new_at_one_of(index, is_it, &[], field_of_play, rng)
+    }
+
+    /// Creates a player who starts on one of `start_positions` that is still empty. If
+    /// `start_positions` is empty the player starts anywhere open, the same as [Player::new].
+    pub fn new_at_one_of(
+        index: usize,
+        is_it: bool,
+        start_positions: &[(usize, usize)],
+        field_of_play: &mut FieldOfPlay,
+        rng: &mut SimRng
+    ) -> Self {
         // Since we are calling init_pos right away it's likely safe to init the positions to 0
         // instead of using Option<usize>.
-        let mut rng = rand::thread_rng();
         let name = format!("p{}", index);
         let mut risk_tolerance: f64 = rng.gen();
-        risk_tolerance = risk_tolerance * 100.0;
+        risk_tolerance *= 100.0;
         let mut player = Player {
             name,
             is_it,
+            is_frozen: false,
+            index,
             x_coordinate: 0,
             y_coordinate: 0,
-            risk_tolerance
+            risk_tolerance,
+            max_speed: 1,
+            max_stamina: 0,
+            stamina: 0,
+            facing: Direction::North,
+            vision: None,
+            last_known_it_location: None,
+            tagged_by: None,
+            tag_back_blocked_until: HashMap::new()
         };
 
-        player.init_position(field_of_play, index);
+        if start_positions.is_empty() {
+            player.init_position(field_of_play, index, rng);
+        } else {
+            player.init_position_from(start_positions, field_of_play, index, rng);
+        }
 
         player
     }
 
     // Looks at the field of play and returns a starting position. It sets the position for the
     // player but the [FieldOfPlay] must be updated with the returned coordinates.
-    fn init_position(&mut self, field_of_play: &mut FieldOfPlay, index: usize) {
-        let y_len = field_of_play.field.len();
-        let x_len = field_of_play.field[0].len();
-        let mut rng = thread_rng();
+    fn init_position(&mut self, field_of_play: &mut FieldOfPlay, index: usize, rng: &mut SimRng) {
+        let y_len = field_of_play.y_len();
+        let x_len = field_of_play.x_len();
         let mut found_pos = false;
 
         while !found_pos {
             let rand_x = rng.gen_range(0..x_len);
             let rand_y = rng.gen_range(0..y_len);
             debug!("checking if x: {}, y: {} is available", rand_x, rand_y);
-            found_pos = field_of_play.field[rand_y][rand_x].is_none();
+            found_pos =
+                field_of_play.get_occupant(rand_x, rand_y).is_none() && field_of_play.is_passable(rand_x, rand_y);
             if found_pos {
                 self.set_location(rand_x, rand_y);
-                field_of_play.field[rand_y][rand_x] = Some(index);
+                field_of_play.set_occupant(rand_x, rand_y, Some(index));
                 if self.is_it {
                     field_of_play.set_last_known_it_location(rand_x, rand_y);
+                    field_of_play.set_it(index, true);
                 }
             }
         }
     }
 
+    // Like init_position, but picks from the given positions. Panics if none of them are empty, so
+    // callers must check there are enough start positions for every player first.
+    fn init_position_from(
+        &mut self,
+        start_positions: &[(usize, usize)],
+        field_of_play: &mut FieldOfPlay,
+        index: usize,
+        rng: &mut SimRng
+    ) {
+        let empty_positions: Vec<&(usize, usize)> = start_positions
+            .iter()
+            .filter(|(x, y)| field_of_play.get_occupant(*x, *y).is_none())
+            .collect();
+        let (x, y) = **empty_positions
+            .choose(rng)
+            .expect("There must be an empty start position for every player.");
+        self.set_location(x, y);
+        field_of_play.set_occupant(x, y, Some(index));
+        if self.is_it {
+            field_of_play.set_last_known_it_location(x, y);
+            field_of_play.set_it(index, true);
+        }
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
     pub fn get_risk_tolerance(&self) -> f64 {
         self.risk_tolerance
     }
 
+    pub fn get_max_speed(&self) -> usize {
+        self.max_speed
+    }
+
+    pub fn get_max_stamina(&self) -> usize {
+        self.max_stamina
+    }
+
+    pub fn get_stamina(&self) -> usize {
+        self.stamina
+    }
+
+    /// Sets the most positions the player can move in a turn and how much stamina they have to
+    /// spend on moving more than one position. Players start with their stamina full. Panics if
+    /// `max_speed` is 0.
+    pub(crate) fn set_fitness(&mut self, max_speed: usize, max_stamina: usize) {
+        assert!(max_speed > 0, "A player must be able to move at least one position.");
+        self.max_speed = max_speed;
+        self.max_stamina = max_stamina;
+        self.stamina = max_stamina;
+    }
+
+    /// Spends or recovers stamina once the player has moved `positions_moved` positions in a turn.
+    /// Every position after the first costs one stamina and standing still recovers
+    /// [STAMINA_RECOVERY].
+    pub(crate) fn tire(&mut self, positions_moved: usize) {
+        if positions_moved == 0 {
+            self.stamina = (self.stamina + STAMINA_RECOVERY).min(self.max_stamina);
+        } else {
+            self.stamina -= positions_moved - 1;
+        }
+    }
+
+    pub fn get_facing(&self) -> Direction {
+        self.facing
+    }
+
+    pub(crate) fn set_facing(&mut self, facing: Direction) {
+        self.facing = facing;
+    }
+
+    pub fn get_vision(&self) -> Option<Vision> {
+        self.vision
+    }
+
+    /// Limits what the player can see. Without vision a player knows where an it player was last
+    /// seen no matter where they are.
+    pub(crate) fn set_vision(&mut self, vision: Vision) {
+        self.vision = Some(vision);
+    }
+
+    /// Where the player believes an it player is, or None if they have not seen one yet.
+    pub fn get_last_known_it_location(&self) -> Option<(usize, usize)> {
+        self.last_known_it_location
+    }
+
+    /// Updates where the player believes an it player is. `it_locations` has the index and location
+    /// of every it player. A player with vision remembers the nearest it player they can see,
+    /// other than themselves, and otherwise keeps believing what they did before. Returns true if the
+    /// player saw an it player. A player without vision takes the last known it location from the
+    /// field of play and `it_locations` is not used.
+    pub(crate) fn look_for_it(
+        &mut self,
+        field_of_play: &FieldOfPlay,
+        it_locations: &[(usize, (usize, usize))]
+    ) -> bool {
+        let vision = match self.vision {
+            Some(vision) => vision,
+            None => {
+                self.last_known_it_location = field_of_play.get_last_known_it_location();
+                return false;
+            }
+        };
+        let location = self.get_location();
+        let nearest_seen = it_locations
+            .iter()
+            .filter(|(index, it_location)| {
+                *index != self.index && vision.can_see(field_of_play, location, self.facing, *it_location)
+            })
+            .min_by_key(|(index, it_location)| {
+                let (x_distance, y_distance) = field_of_play.get_axis_distances(location, *it_location);
+                (x_distance * x_distance + y_distance * y_distance, *index)
+            });
+        if let Some((_, it_location)) = nearest_seen {
+            self.last_known_it_location = Some(*it_location);
+        }
+
+        nearest_seen.is_some()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_risk_tolerance(&mut self, risk_tolerance: f64) {
+        self.risk_tolerance = risk_tolerance;
+    }
+
     /// Looks at the field of play and takes at least one action. If the player is not it, it will
     /// attempt to move. If the player is it, it will attempt to tag any nearby players and also
     /// move. The tag action will only occur before the move action. If a player is it, does not
     /// tag anyone, and moves to a new position where they are able to tag another player they must
-    /// wait until their next turn.
-    pub fn take_action(&mut self, field_of_play: &FieldOfPlay, last_it_index: usize) -> Vec<Action> {
+    /// wait until their next turn. Where the player moves to is decided by the [MovementStrategy]
+    /// for whether or not they are it once any tag has happened.
+    ///
+    /// In freeze tag and infection the it player stays it after tagging. In freeze tag frozen players
+    /// take no actions, and a player who is not it may rescue an adjacent frozen player before moving.
+    pub fn take_action(
+        &mut self,
+        field_of_play: &FieldOfPlay,
+        game_mode: GameMode,
+        strategies: &Strategies,
+        rng: &mut SimRng
+    ) -> Vec<Action> {
         let mut actions: Vec<Action> = Vec::new();
+        if self.is_frozen {
+            return actions;
+        }
         if self.is_it {
-            if let Some(newly_tagged_index) = self.get_taggable_player(field_of_play, last_it_index) {
-                self.is_it = false;
+            if let Some(newly_tagged_index) = self.get_taggable_player(field_of_play) {
+                if game_mode == GameMode::Classic {
+                    self.is_it = false;
+                }
                 actions.push(Action::new_tag(newly_tagged_index));
             }
+        } else if game_mode == GameMode::Freeze {
+            if let Some(rescued_index) = self.get_rescuable_player(field_of_play) {
+                actions.push(Action::new_rescue(rescued_index));
+            }
         }
-        actions.push(self.take_move_action(field_of_play));
+        actions.push(self.take_move_action(field_of_play, strategies.for_player(self), rng));
 
         actions
     }
 
-    fn get_taggable_player(&self, field_of_play: &FieldOfPlay, last_it_index: usize) -> Option<usize> {
-        let adjacent_players = field_of_play.get_adjacent_player_indices(self.x_coordinate, self.y_coordinate);
-        let taggable_players = adjacent_players.into_iter().find(|ap| *ap != last_it_index);
-        debug!("Taggable player indices adjacent to {:?}: {:?}", self, taggable_players);
+    /// Returns true if this player is allowed to tag the player at `index`, ignoring whether they
+    /// are adjacent or already it. Players who tagged this player can not be tagged back until their
+    /// tag-back cooldown has passed.
+    pub fn can_tag(&self, index: usize) -> bool {
+        !self.tag_back_blocked_until.contains_key(&index)
+    }
 
-        taggable_players
+    pub fn get_tagged_by(&self) -> Option<usize> {
+        self.tagged_by
     }
 
-    fn take_move_action(&mut self, field_of_play: &FieldOfPlay) -> Action {
-        let (x, y) = self.move_to_empty_position(field_of_play);
-        self.set_location(x, y);
+    /// Makes this player it. `tagger_index` is the player who tagged them on `turn_num`. With a
+    /// `tag_back_cooldown` this player can not tag the tagger for that many turns. Without one this
+    /// player can not tag the tagger back until someone else tags this player, which is the classic
+    /// no tag-backs rule.
+    pub fn become_it(&mut self, tagger_index: usize, turn_num: usize, tag_back_cooldown: Option<usize>) {
+        self.is_it = true;
+        self.tagged_by = Some(tagger_index);
+        match tag_back_cooldown {
+            Some(cooldown) => {
+                self.tag_back_blocked_until.insert(tagger_index, turn_num + cooldown);
+            }
+            None => {
+                self.tag_back_blocked_until.clear();
+                self.tag_back_blocked_until.insert(tagger_index, usize::MAX);
+            }
+        }
+    }
 
-        Action::new_move(x, y)
+    /// Lifts any tag-back restrictions whose cooldown has passed. Called at the start of the player's
+    /// turn.
+    pub fn expire_tag_back_cooldowns(&mut self, turn_num: usize) {
+        self.tag_back_blocked_until
+            .retain(|_, blocked_until| *blocked_until > turn_num);
     }
 
-    fn set_location(&mut self, x: usize, y: usize) {
-        self.x_coordinate = x;
-        self.y_coordinate = y;
+    fn get_taggable_player(&self, field_of_play: &FieldOfPlay) -> Option<usize> {
+        let adjacent_players = field_of_play.get_adjacent_player_indices(self.x_coordinate, self.y_coordinate);
+        let taggable_players = adjacent_players
+            .into_iter()
+            .find(|ap| self.can_tag(*ap) && !field_of_play.is_it(*ap) && !field_of_play.is_frozen(*ap));
+        debug!("Taggable player indices adjacent to {:?}: {:?}", self, taggable_players);
+
+        taggable_players
     }
 
-    pub fn get_location(&self) -> (usize, usize) {
-        (self.x_coordinate, self.y_coordinate)
+    fn get_rescuable_player(&self, field_of_play: &FieldOfPlay) -> Option<usize> {
+        field_of_play
+            .get_adjacent_player_indices(self.x_coordinate, self.y_coordinate)
+            .into_iter()
+            .find(|ap| field_of_play.is_frozen(*ap))
     }
 
-    fn move_to_empty_position(&self, field_of_play: &FieldOfPlay) -> (usize, usize) {
-        let mut rng = rand::thread_rng();
-        let mut found_location = false;
-        let mut retries_remaining = 1000;
-        let mut x_coordinate = self.x_coordinate;
-        let mut y_coordinate = self.y_coordinate;
-
-        while !found_location {
-            let rand_direction = rng.gen_range(0..8);
-            match rand_direction {
-                0 => {
-                    // WEST
-                    if field_of_play.is_position_valid_and_empty(Direction::West, self.x_coordinate, self.y_coordinate)
-                    {
-                        x_coordinate = self.x_coordinate - 1;
-                        found_location = true;
-                    }
+    // Asks the strategy where to move and uses the first legal move. A player who can move more
+    // than one position this turn is asked again from wherever the last move left them, until they
+    // have moved as far as they can or stop. If the strategy does not return a legal move the player
+    // stays where they are.
+    fn take_move_action(
+        &mut self,
+        field_of_play: &FieldOfPlay,
+        strategy: &dyn MovementStrategy,
+        rng: &mut SimRng
+    ) -> Action {
+        let start = self.get_location();
+        let max_positions = self.positions_this_turn(field_of_play);
+        let mut path = Vec::new();
+        while path.len() < max_positions {
+            let desired_actions = strategy.choose_actions(field_of_play, self, rng);
+            let steps = desired_actions.into_iter().find_map(|action| {
+                let steps = action.path();
+                // Staying put, or doubling back, ends the move where the player is.
+                let stops = steps == [self.get_location()] || steps.iter().any(|step| path.contains(step));
+                if action.action == ActionType::Move && stops {
+                    return Some(Vec::new());
                 }
-                1 => {
-                    // EAST
-                    if field_of_play.is_position_valid_and_empty(Direction::East, self.x_coordinate, self.y_coordinate)
-                    {
-                        x_coordinate = self.x_coordinate + 1;
-                        found_location = true;
-                    }
+                let whole_path = [path.as_slice(), steps.as_slice()].concat();
+                if action.action == ActionType::Move
+                    && whole_path.len() <= max_positions
+                    && field_of_play.is_open_path(start, &whole_path)
+                {
+                    return Some(steps);
                 }
-                2 => {
-                    // NORTH
-                    if field_of_play.is_position_valid_and_empty(Direction::North, self.x_coordinate, self.y_coordinate)
-                    {
-                        y_coordinate = self.y_coordinate - 1;
-                        found_location = true;
-                    }
-                }
-                3 => {
-                    // SOUTH
-                    if field_of_play.is_position_valid_and_empty(Direction::South, self.x_coordinate, self.y_coordinate)
-                    {
-                        y_coordinate = self.y_coordinate + 1;
-                        found_location = true;
-                    }
-                }
-                4 => {
-                    // NORTHWEST
-                    if field_of_play.is_position_valid_and_empty(
-                        Direction::NorthWest,
-                        self.x_coordinate,
-                        self.y_coordinate
-                    ) {
-                        y_coordinate = self.y_coordinate - 1;
-                        x_coordinate = self.x_coordinate - 1;
-                        found_location = true;
-                    }
-                }
-                5 => {
-                    // NORTHEAST
-                    if field_of_play.is_position_valid_and_empty(
-                        Direction::NorthEast,
-                        self.x_coordinate,
-                        self.y_coordinate
-                    ) {
-                        y_coordinate = self.y_coordinate - 1;
-                        x_coordinate = self.x_coordinate + 1;
-                        found_location = true;
-                    }
-                }
-                6 => {
-                    // SOUTHWEST
-                    if field_of_play.is_position_valid_and_empty(
-                        Direction::SouthWest,
-                        self.x_coordinate,
-                        self.y_coordinate
-                    ) {
-                        y_coordinate = self.y_coordinate + 1;
-                        x_coordinate = self.x_coordinate - 1;
-                        found_location = true;
-                    }
-                }
-                7 => {
-                    //SOUTHEAST
-                    if field_of_play.is_position_valid_and_empty(
-                        Direction::SouthEast,
-                        self.x_coordinate,
-                        self.y_coordinate
-                    ) {
-                        y_coordinate = self.y_coordinate + 1;
-                        x_coordinate = self.x_coordinate + 1;
-                        found_location = true;
-                    }
-                }
-                _ => unreachable!()
-            };
-
-            // It is better to move than get stuck because we do not want to get closer to the it
-            // player. So we only try to find a new position that isn't closer to the it player if
-            // we have at least 100 retries remaining.
-            if self.is_new_coordinates_too_close_to_it_player(x_coordinate, y_coordinate, &field_of_play)
-                && retries_remaining > 100
-            {
-                found_location = false;
-            }
-
-            // It is possible that players will get bunched up at the edge of the field and the
-            // current player has nowhere to go. If this is the case then skip this player and let
-            // the next one try to move.
-            retries_remaining += -1;
-            if retries_remaining <= 0 {
-                error!("Player: {} is stuck", self.name);
-                found_location = true;
+                warn!(
+                    "{} ignoring illegal action from {}: {:?}",
+                    self.name,
+                    strategy.name(),
+                    action
+                );
+                None
+            });
+            match steps.as_ref().and_then(|steps| steps.last()) {
+                Some((x, y)) => self.set_location(*x, *y),
+                None => break
             }
+            path.extend(steps.unwrap_or_default());
         }
 
-        (x_coordinate, y_coordinate)
-    }
-
-    // distance between two sets of points is d=sqrt(x2-x1)^2 + (y2-y1)^2. If the x and y input are
-    // closer to the it player than the player's current coordinates we return false. Using floats
-    // for calc instead of and arb precision type, like BigDecimal means we may have slightly less
-    // reliable results, but an argument can be made that this makes the agents more human-like.
-    fn is_new_coordinates_too_close_to_it_player(&self, x: usize, y: usize, field_of_play: &FieldOfPlay) -> bool {
-        let it_coordinates = field_of_play.get_last_known_it_location();
-        if let Some((it_x, it_y)) = it_coordinates {
-            let new_distance = (((it_x - x).pow(2) + (it_y - y).pow(2)) as f64).sqrt();
-            let current_distance =
-                (((it_x - self.x_coordinate).pow(2) + (it_y - self.y_coordinate).pow(2)) as f64).sqrt();
-            if new_distance < current_distance {
-                return self.is_new_distance_outside_risk_tolerance(new_distance, current_distance);
-            }
+        if path.is_empty() {
+            Action::new_move(start.0, start.1)
+        } else {
+            Action::new_path(&path)
         }
-
-        false
     }
 
-    // players with greater risk tolerance take more risks. They're willing to move closer to the
-    // it player.
-    fn is_new_distance_outside_risk_tolerance(&self, new_distance: f64, current_distance: f64) -> bool {
-        let pct_chg = ((new_distance - current_distance).abs() / current_distance) * 100.0;
-        if pct_chg > self.risk_tolerance {
-            true
+    // The most positions the player moves this turn. Players who are it always sprint, and everyone
+    // else only sprints when they believe the it player is nearby. Either way only as far as their
+    // stamina allows.
+    fn positions_this_turn(&self, field_of_play: &FieldOfPlay) -> usize {
+        let it_is_near = self.last_known_it_location.is_some_and(|it_location| {
+            let (x_distance, y_distance) = field_of_play.get_axis_distances(self.get_location(), it_location);
+            x_distance.max(y_distance) <= SPRINT_DISTANCE
+        });
+        if self.is_it || it_is_near {
+            self.max_speed.min(self.stamina + 1)
         } else {
-            false
+            1
         }
     }
+
+    pub(crate) fn set_location(&mut self, x: usize, y: usize) {
+        self.x_coordinate = x;
+        self.y_coordinate = y;
+    }
+
+    pub fn get_location(&self) -> (usize, usize) {
+        (self.x_coordinate, self.y_coordinate)
+    }
 }
 
 #[cfg(test)]
-use super::action::ActionType;
+use super::rng::new_rng;
+#[cfg(test)]
+use super::terrain::Terrain;
 
 #[test]
-// Creating a new player involves randomly placing the player on the field of play. If we wanted to
-// make this test faster / more deterministic and realisitc we could wrap the calls to the rand
-// crate in a function that we could mock using a library like mocktopus.
+// Creating a new player involves randomly placing the player on the field of play. The field is
+// almost full so the result does not depend on the seed.
 fn player_init_test() {
     // populate an almost full field and ensure the new player is in the only empty spot
     let mut field_of_play = FieldOfPlay::new(3, 3);
-    field_of_play.field[0][0] = Some(1);
-    field_of_play.field[0][1] = Some(2);
-    field_of_play.field[0][2] = Some(3);
-    field_of_play.field[1][0] = Some(4);
-    field_of_play.field[1][2] = Some(5);
-    field_of_play.field[2][0] = Some(6);
-    field_of_play.field[2][1] = Some(7);
-    field_of_play.field[2][2] = Some(8);
+    field_of_play.set_occupant(0, 0, Some(1));
+    field_of_play.set_occupant(1, 0, Some(2));
+    field_of_play.set_occupant(2, 0, Some(3));
+    field_of_play.set_occupant(0, 1, Some(4));
+    field_of_play.set_occupant(2, 1, Some(5));
+    field_of_play.set_occupant(0, 2, Some(6));
+    field_of_play.set_occupant(1, 2, Some(7));
+    field_of_play.set_occupant(2, 2, Some(8));
 
-    let new_player = Player::new(9, true, &mut field_of_play);
+    let new_player = Player::new(9, true, &mut field_of_play, &mut new_rng(0));
 
     assert_eq!(new_player.name, "p9".to_owned());
-    assert_eq!(new_player.is_it, true);
+    assert!(new_player.is_it);
     assert_eq!(new_player.get_location(), (1, 1));
 }
 
@@ -278,22 +436,23 @@ fn player_init_test() {
 fn player_take_action_test() {
     // Test that I move to the only valid and empty location
     let mut field_of_play = FieldOfPlay::new(3, 3);
-    field_of_play.field[0][0] = Some(1);
-    field_of_play.field[0][1] = Some(2);
-    field_of_play.field[0][2] = Some(3);
+    field_of_play.set_occupant(0, 0, Some(1));
+    field_of_play.set_occupant(1, 0, Some(2));
+    field_of_play.set_occupant(2, 0, Some(3));
     // We temporary fill this position because we don't want the new user to init into it.
-    field_of_play.field[1][0] = Some(100);
-    field_of_play.field[1][1] = None;
-    field_of_play.field[1][2] = Some(4);
-    field_of_play.field[2][0] = Some(5);
-    field_of_play.field[2][1] = Some(6);
-    field_of_play.field[2][2] = Some(7);
-
-    let mut player = Player::new(9, false, &mut field_of_play);
+    field_of_play.set_occupant(0, 1, Some(100));
+    field_of_play.set_occupant(1, 1, None);
+    field_of_play.set_occupant(2, 1, Some(4));
+    field_of_play.set_occupant(0, 2, Some(5));
+    field_of_play.set_occupant(1, 2, Some(6));
+    field_of_play.set_occupant(2, 2, Some(7));
+
+    let mut rng = new_rng(0);
+    let mut player = Player::new(9, false, &mut field_of_play, &mut rng);
     assert_eq!(player.get_location(), (1, 1));
     // now we set this position to empty so player will move to it.
-    field_of_play.field[1][0] = None;
-    let actions = player.take_action(&field_of_play, 0);
+    field_of_play.set_occupant(0, 1, None);
+    let actions = player.take_action(&field_of_play, GameMode::Classic, &Strategies::default(), &mut rng);
     assert_eq!(actions.len(), 1);
     assert_eq!(actions[0].action, ActionType::Move);
     assert_eq!(actions[0].x_coordinate, Some(0));
@@ -301,17 +460,17 @@ fn player_take_action_test() {
 
     // The player is at (0,1). Now remove all but two of the other players
     // of which only one is taggable.
-    field_of_play.field[0][0] = Some(1);
-    field_of_play.field[0][1] = Some(2);
-    field_of_play.field[0][2] = None;
-    field_of_play.field[1][0] = Some(9);
-    field_of_play.field[1][1] = None;
-    field_of_play.field[1][2] = None;
-    field_of_play.field[2][0] = None;
-    field_of_play.field[2][1] = None;
-    field_of_play.field[2][2] = None;
-    player.is_it = true;
-    let actions = player.take_action(&field_of_play, 1);
+    field_of_play.set_occupant(0, 0, Some(1));
+    field_of_play.set_occupant(1, 0, Some(2));
+    field_of_play.set_occupant(2, 0, None);
+    field_of_play.set_occupant(0, 1, Some(9));
+    field_of_play.set_occupant(1, 1, None);
+    field_of_play.set_occupant(2, 1, None);
+    field_of_play.set_occupant(0, 2, None);
+    field_of_play.set_occupant(1, 2, None);
+    field_of_play.set_occupant(2, 2, None);
+    player.become_it(1, 0, None);
+    let actions = player.take_action(&field_of_play, GameMode::Classic, &Strategies::default(), &mut rng);
 
     assert!(!player.is_it);
     assert_eq!(actions.len(), 2);
@@ -323,7 +482,7 @@ fn player_take_action_test() {
 #[test]
 fn player_get_risk_tolerance_test() {
     let mut field_of_play = FieldOfPlay::new(3, 3);
-    let mut player = Player::new(9, false, &mut field_of_play);
+    let mut player = Player::new(9, false, &mut field_of_play, &mut new_rng(0));
 
     player.risk_tolerance = 17.0;
 
@@ -333,7 +492,7 @@ fn player_get_risk_tolerance_test() {
 #[test]
 fn player_get_location_test() {
     let mut field_of_play = FieldOfPlay::new(3, 3);
-    let mut player = Player::new(9, false, &mut field_of_play);
+    let mut player = Player::new(9, false, &mut field_of_play, &mut new_rng(0));
 
     player.x_coordinate = 1;
     player.y_coordinate = 2;
@@ -342,35 +501,255 @@ fn player_get_location_test() {
 }
 
 #[test]
-// Normally, you would not test private functions like this. But, for the purposes of this exercise
-// we will for now due to time constraints
-fn is_new_coordinates_too_close_test() {
+fn player_new_same_seed_is_reproducible_test() {
+    let mut first_field = FieldOfPlay::new(10, 10);
+    let mut second_field = FieldOfPlay::new(10, 10);
+    let first = Player::new(1, false, &mut first_field, &mut new_rng(7));
+    let second = Player::new(1, false, &mut second_field, &mut new_rng(7));
+
+    assert_eq!(first.get_location(), second.get_location());
+    assert_eq!(first.get_risk_tolerance(), second.get_risk_tolerance());
+}
+
+#[cfg(test)]
+struct FixedMoveStrategy(usize, usize);
+
+#[cfg(test)]
+impl MovementStrategy for FixedMoveStrategy {
+    fn choose_actions(&self, _: &FieldOfPlay, _: &Player, _: &mut SimRng) -> Vec<Action> {
+        vec![Action::new_move(self.0, self.1)]
+    }
+
+    fn name(&self) -> &str {
+        "fixed"
+    }
+}
+
+#[cfg(test)]
+fn fixed_move_strategies(x: usize, y: usize) -> Strategies {
+    Strategies {
+        runner: Box::new(FixedMoveStrategy(x, y)),
+        chaser: Box::new(FixedMoveStrategy(x, y))
+    }
+}
+
+#[test]
+fn player_take_action_ignores_illegal_moves_test() {
+    let mut field_of_play = FieldOfPlay::new(5, 5);
+    let mut rng = new_rng(0);
+    let mut player = Player::new(1, false, &mut field_of_play, &mut rng);
+    player.set_location(2, 2);
+
+    // too far away
+    let actions = player.take_action(
+        &field_of_play,
+        GameMode::Classic,
+        &fixed_move_strategies(4, 4),
+        &mut rng
+    );
+    assert_eq!(actions[0].x_coordinate, Some(2));
+    assert_eq!(actions[0].y_coordinate, Some(2));
+
+    // occupied
+    field_of_play.set_occupant(3, 3, Some(2));
+    let actions = player.take_action(
+        &field_of_play,
+        GameMode::Classic,
+        &fixed_move_strategies(3, 3),
+        &mut rng
+    );
+    assert_eq!(player.get_location(), (2, 2));
+    assert_eq!(actions[0].x_coordinate, Some(2));
+
+    // legal
+    player.take_action(
+        &field_of_play,
+        GameMode::Classic,
+        &fixed_move_strategies(1, 3),
+        &mut rng
+    );
+    assert_eq!(player.get_location(), (1, 3));
+}
+
+#[test]
+fn player_can_tag_test() {
     let mut field_of_play = FieldOfPlay::new(3, 3);
-    let mut player = Player::new(1, false, &mut field_of_play);
-    let mut it_player = Player::new(2, true, &mut field_of_play);
-    player.risk_tolerance = 10.0;
-    it_player.x_coordinate = 2;
-    it_player.y_coordinate = 2;
-    field_of_play.set_last_known_it_location(it_player.x_coordinate, it_player.y_coordinate);
-
-    // test moving away from it
-    player.x_coordinate = 2;
-    player.y_coordinate = 1;
-
-    assert_eq!(
-        field_of_play.get_last_known_it_location(),
-        Some((it_player.x_coordinate, it_player.y_coordinate))
+    let mut player = Player::new(1, false, &mut field_of_play, &mut new_rng(0));
+    assert!(player.can_tag(2));
+
+    player.become_it(2, 0, None);
+    assert!(player.is_it);
+    assert_eq!(player.get_tagged_by(), Some(2));
+    assert!(!player.can_tag(2));
+    assert!(player.can_tag(3));
+
+    // Being tagged by someone else lifts the classic restriction.
+    player.become_it(3, 1, None);
+    assert!(player.can_tag(2));
+    assert!(!player.can_tag(3));
+    player.expire_tag_back_cooldowns(1_000);
+    assert!(!player.can_tag(3));
+}
+
+#[test]
+fn player_tag_back_cooldown_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut player = Player::new(1, false, &mut field_of_play, &mut new_rng(0));
+
+    player.become_it(2, 10, Some(3));
+    player.become_it(3, 11, Some(3));
+    player.expire_tag_back_cooldowns(12);
+    assert!(!player.can_tag(2));
+    assert!(!player.can_tag(3));
+
+    player.expire_tag_back_cooldowns(13);
+    assert!(player.can_tag(2));
+    assert!(!player.can_tag(3));
+
+    player.expire_tag_back_cooldowns(14);
+    assert!(player.can_tag(3));
+}
+
+#[test]
+fn player_freeze_tag_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut rng = new_rng(0);
+    let mut it_player = Player::new(0, true, &mut field_of_play, &mut rng);
+    let mut runner = Player::new(1, false, &mut field_of_play, &mut rng);
+    for (_, (x, y)) in field_of_play.get_player_positions() {
+        field_of_play.set_occupant(x, y, None);
+    }
+    field_of_play.set_occupant(0, 0, Some(0));
+    field_of_play.set_occupant(1, 0, Some(1));
+    field_of_play.set_occupant(0, 1, Some(2));
+    it_player.set_location(0, 0);
+    runner.set_location(1, 0);
+
+    // Player 2 is frozen so the it player tags player 1 and stays it.
+    field_of_play.set_frozen(2, true);
+    let actions = it_player.take_action(&field_of_play, GameMode::Freeze, &Strategies::default(), &mut rng);
+    assert!(it_player.is_it);
+    assert_eq!(actions[0].action, ActionType::Tag);
+    assert_eq!(actions[0].new_it_index, Some(1));
+
+    // The runner rescues player 2.
+    let actions = runner.take_action(&field_of_play, GameMode::Freeze, &Strategies::default(), &mut rng);
+    assert_eq!(actions[0].action, ActionType::Rescue);
+    assert_eq!(actions[0].rescued_index, Some(2));
+
+    // Frozen players do nothing.
+    runner.is_frozen = true;
+    let actions = runner.take_action(&field_of_play, GameMode::Freeze, &Strategies::default(), &mut rng);
+    assert!(actions.is_empty());
+}
+
+#[test]
+fn player_avoids_obstacles_test() {
+    // Only one open and empty position, the rest are either occupied or obstacles.
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    (0..3).for_each(|y| (0..3).for_each(|x| field_of_play.set_terrain(x, y, Terrain::Tree)));
+    field_of_play.set_terrain(1, 1, Terrain::Open);
+    field_of_play.set_terrain(2, 1, Terrain::Open);
+    field_of_play.set_occupant(2, 1, Some(1));
+    let mut rng = new_rng(0);
+    let mut player = Player::new(2, false, &mut field_of_play, &mut rng);
+    assert_eq!(player.get_location(), (1, 1));
+
+    let actions = player.take_action(
+        &field_of_play,
+        GameMode::Classic,
+        &fixed_move_strategies(0, 0),
+        &mut rng
     );
-    assert!(!player.is_new_coordinates_too_close_to_it_player(0, 0, &field_of_play));
-
-    // test moving closer but within tolerance
-    player.x_coordinate = 0;
-    player.y_coordinate = 0;
-    assert!(player.is_new_coordinates_too_close_to_it_player(0, 1, &field_of_play));
-
-    // test moving closer but outside tolerance
-    player.x_coordinate = 0;
-    player.y_coordinate = 0;
-    player.risk_tolerance = 25.0;
-    assert!(!player.is_new_coordinates_too_close_to_it_player(0, 1, &field_of_play));
+    assert_eq!(actions[0].x_coordinate, Some(1));
+    assert_eq!(actions[0].y_coordinate, Some(1));
+}
+
+#[cfg(test)]
+struct EastStrategy;
+
+#[cfg(test)]
+impl MovementStrategy for EastStrategy {
+    fn choose_actions(&self, _: &FieldOfPlay, player: &Player, _: &mut SimRng) -> Vec<Action> {
+        let (x, y) = player.get_location();
+        vec![Action::new_move(x + 1, y)]
+    }
+
+    fn name(&self) -> &str {
+        "east"
+    }
+}
+
+#[test]
+fn player_sprint_test() {
+    let mut field_of_play = FieldOfPlay::new(8, 3);
+    let mut rng = new_rng(0);
+    let mut it_player = Player::new(0, true, &mut field_of_play, &mut rng);
+    let mut runner = Player::new(1, false, &mut field_of_play, &mut rng);
+    // A fresh field, where nobody has seen the it player yet.
+    field_of_play = FieldOfPlay::new(8, 3);
+    field_of_play.set_occupant(0, 0, Some(0));
+    field_of_play.set_occupant(0, 2, Some(1));
+    it_player.set_location(0, 0);
+    runner.set_location(0, 2);
+    it_player.set_fitness(3, 1);
+    runner.set_fitness(3, 1);
+    let strategies = Strategies {
+        runner: Box::new(EastStrategy),
+        chaser: Box::new(EastStrategy)
+    };
+
+    // The it player could move three positions but only has the stamina for two.
+    let actions = it_player.take_action(&field_of_play, GameMode::Classic, &strategies, &mut rng);
+    assert_eq!(actions[0].path(), vec![(1, 0), (2, 0)]);
+    it_player.tire(2);
+    assert_eq!(it_player.get_stamina(), 0);
+    field_of_play.set_occupant(0, 0, None);
+    field_of_play.set_occupant(2, 0, Some(0));
+    let actions = it_player.take_action(&field_of_play, GameMode::Classic, &strategies, &mut rng);
+    assert_eq!(actions[0].path(), vec![(3, 0)]);
+    // Standing still recovers stamina, up to the max.
+    it_player.tire(0);
+    assert_eq!(it_player.get_stamina(), 1);
+
+    // The runner has no reason to sprint until they see the it player.
+    let actions = runner.take_action(&field_of_play, GameMode::Classic, &strategies, &mut rng);
+    assert_eq!(actions[0].path(), vec![(1, 2)]);
+    runner.set_location(0, 2);
+    field_of_play.set_last_known_it_location(2, 0);
+    runner.look_for_it(&field_of_play, &[]);
+    let actions = runner.take_action(&field_of_play, GameMode::Classic, &strategies, &mut rng);
+    assert_eq!(actions[0].path(), vec![(1, 2), (2, 2)]);
+}
+
+#[test]
+fn player_look_for_it_test() {
+    let mut field_of_play = FieldOfPlay::new(7, 7);
+    let mut rng = new_rng(0);
+    let mut player = Player::new(0, false, &mut field_of_play, &mut rng);
+    field_of_play = FieldOfPlay::new(7, 7);
+    field_of_play.set_occupant(3, 6, Some(0));
+    player.set_location(3, 6);
+
+    // Without vision the player knows what the field of play knows.
+    field_of_play.set_last_known_it_location(0, 0);
+    assert!(!player.look_for_it(&field_of_play, &[]));
+    assert_eq!(player.get_last_known_it_location(), Some((0, 0)));
+
+    // With vision they remember the nearest it player they can see, and keep believing it once it
+    // is out of sight.
+    player.set_vision(Vision { range: 4, angle: 90 });
+    let it_locations = [(1, (3, 1)), (2, (2, 3)), (3, (3, 5))];
+    field_of_play.set_occupant(3, 5, Some(3));
+    player.set_facing(Direction::South);
+    assert!(!player.look_for_it(&field_of_play, &it_locations));
+    assert_eq!(player.get_last_known_it_location(), Some((0, 0)));
+    player.set_facing(Direction::North);
+    assert!(player.look_for_it(&field_of_play, &it_locations));
+    assert_eq!(player.get_last_known_it_location(), Some((3, 5)));
+    field_of_play.set_occupant(3, 5, None);
+    assert!(player.look_for_it(&field_of_play, &it_locations[..2]));
+    assert_eq!(player.get_last_known_it_location(), Some((2, 3)));
+    assert!(!player.look_for_it(&field_of_play, &it_locations[..1]));
+    assert_eq!(player.get_last_known_it_location(), Some((2, 3)));
 }
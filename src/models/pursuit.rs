@@ -0,0 +1,626 @@
+use super::action::Action;
+use super::direction::Direction;
+use super::field_of_play::FieldOfPlay;
+use super::player::Player;
+use super::rng::SimRng;
+use super::strategy::{MovementStrategy, RandomAvoidanceStrategy};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// The movement strategies available to the it player. This is what the CLI uses to pick a
+/// chaser strategy for a run.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChaserStrategy {
+    #[default]
+    Random,
+    Nearest,
+    Cluster,
+    Pathfinding
+}
+
+impl ChaserStrategy {
+    pub const NAMES: [&'static str; 4] = ["random", "nearest", "cluster", "pathfinding"];
+
+    pub fn build(self) -> Box<dyn MovementStrategy> {
+        match self {
+            ChaserStrategy::Random => Box::new(RandomAvoidanceStrategy),
+            ChaserStrategy::Nearest => Box::new(NearestPlayerPursuit::default()),
+            ChaserStrategy::Cluster => Box::new(DensestClusterPursuit::default()),
+            ChaserStrategy::Pathfinding => Box::new(PathfindingPursuit::default())
+        }
+    }
+}
+
+impl FromStr for ChaserStrategy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "random" => Ok(ChaserStrategy::Random),
+            "nearest" => Ok(ChaserStrategy::Nearest),
+            "cluster" => Ok(ChaserStrategy::Cluster),
+            "pathfinding" => Ok(ChaserStrategy::Pathfinding),
+            _ => Err(format!(
+                "unknown chaser strategy: {}. Must be one of: {}",
+                name,
+                ChaserStrategy::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for ChaserStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ChaserStrategy::Random => "random",
+            ChaserStrategy::Nearest => "nearest",
+            ChaserStrategy::Cluster => "cluster",
+            ChaserStrategy::Pathfinding => "pathfinding"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Greedily moves towards the closest player that can be tagged. Players up to `search_radius`
+/// positions away are looked for on the field as it is. Beyond that the chaser heads for wherever
+/// the closest player was at the start of the turn, so a chaser far from everyone does not search
+/// the whole field on every decision.
+#[derive(Clone, Debug)]
+pub struct NearestPlayerPursuit {
+    pub search_radius: usize,
+    targets: TargetGrid
+}
+
+impl Default for NearestPlayerPursuit {
+    fn default() -> Self {
+        NearestPlayerPursuit {
+            search_radius: 4,
+            targets: TargetGrid::default()
+        }
+    }
+}
+
+impl MovementStrategy for NearestPlayerPursuit {
+    fn choose_actions(&self, field_of_play: &FieldOfPlay, player: &Player, rng: &mut SimRng) -> Vec<Action> {
+        match self.find_target(field_of_play, player) {
+            Some(target) => pursue(field_of_play, player, target, rng),
+            None => RandomAvoidanceStrategy.choose_actions(field_of_play, player, rng)
+        }
+    }
+
+    fn start_turn(&mut self, field_of_play: &FieldOfPlay) {
+        self.targets = TargetGrid::new(field_of_play, TargetGrid::BUCKET_SIZE);
+    }
+
+    fn name(&self) -> &str {
+        "nearest"
+    }
+}
+
+impl NearestPlayerPursuit {
+    fn find_target(&self, field_of_play: &FieldOfPlay, player: &Player) -> Option<(usize, usize)> {
+        find_nearest_target(field_of_play, player, self.search_radius)
+            .or_else(|| self.targets.find_nearest(field_of_play, player))
+    }
+}
+
+// Every player worth chasing at the start of a turn, sorted into square buckets so a chaser can look
+// in the buckets around them first rather than at every player.
+#[derive(Clone, Debug, Default)]
+struct TargetGrid {
+    bucket_size: usize,
+    x_buckets: usize,
+    y_buckets: usize,
+    // The targets in bucket b, counting row by row, are targets[bucket_starts[b]..bucket_starts[b + 1]].
+    bucket_starts: Vec<usize>,
+    targets: Vec<(usize, (usize, usize))>
+}
+
+impl TargetGrid {
+    const BUCKET_SIZE: usize = 16;
+
+    fn new(field_of_play: &FieldOfPlay, bucket_size: usize) -> Self {
+        let bucket_size = bucket_size.max(1);
+        let x_buckets = field_of_play.x_len().div_ceil(bucket_size);
+        let y_buckets = field_of_play.y_len().div_ceil(bucket_size);
+        let bucket_of = |(x, y): (usize, usize)| (y / bucket_size) * x_buckets + x / bucket_size;
+        let mut targets = find_targets(field_of_play);
+        targets.sort_by_key(|(_, position)| bucket_of(*position));
+        let mut bucket_starts = vec![0; x_buckets * y_buckets + 1];
+        targets
+            .iter()
+            .for_each(|(_, position)| bucket_starts[bucket_of(*position) + 1] += 1);
+        (1..bucket_starts.len()).for_each(|bucket| bucket_starts[bucket] += bucket_starts[bucket - 1]);
+
+        TargetGrid {
+            bucket_size,
+            x_buckets,
+            y_buckets,
+            bucket_starts,
+            targets
+        }
+    }
+
+    // The closest target the player can tag, from where targets were at the start of the turn. Looks
+    // through rings of buckets around the player's own until no bucket further out could hold anyone
+    // closer, or through every target once that is quicker.
+    fn find_nearest(&self, field_of_play: &FieldOfPlay, player: &Player) -> Option<(usize, usize)> {
+        if self.targets.is_empty() {
+            return None;
+        }
+        let location = player.get_location();
+        let mut nearest: Option<((usize, usize), (usize, usize))> = None;
+        // Only targets closer than the nearest so far are checked, as checking is the slow part.
+        let consider = |nearest: &mut Option<_>, (index, position): &(usize, (usize, usize))| {
+            let key = distance_key(field_of_play, location, *position);
+            if nearest.is_none_or(|(nearest_key, _)| key < nearest_key) && is_target(field_of_play, player, *index) {
+                *nearest = Some((key, *position));
+            }
+        };
+        let topology = field_of_play.topology();
+        let bucket = (location.0 / self.bucket_size, location.1 / self.bucket_size);
+        let mut buckets_searched = 0;
+        for ring in 0..=self.x_buckets.max(self.y_buckets) as isize {
+            if buckets_searched > self.targets.len() {
+                self.targets.iter().for_each(|target| consider(&mut nearest, target));
+                break;
+            }
+            for y_offset in -ring..=ring {
+                let x_offsets: Vec<isize> = if y_offset.abs() == ring {
+                    (-ring..=ring).collect()
+                } else {
+                    vec![-ring, ring]
+                };
+                let bucket_y = match bucket_at_offset(bucket.1, y_offset, self.y_buckets, topology.wraps_y()) {
+                    Some(bucket_y) => bucket_y,
+                    None => continue
+                };
+                for x_offset in x_offsets {
+                    if let Some(bucket_x) = bucket_at_offset(bucket.0, x_offset, self.x_buckets, topology.wraps_x()) {
+                        let bucket = bucket_y * self.x_buckets + bucket_x;
+                        self.targets[self.bucket_starts[bucket]..self.bucket_starts[bucket + 1]]
+                            .iter()
+                            .for_each(|target| consider(&mut nearest, target));
+                        buckets_searched += 1;
+                    }
+                }
+            }
+            // Anyone in the next ring out is at least this far away.
+            let next_ring_distance = ring as usize * self.bucket_size + 1;
+            if nearest.is_some_and(|(key, _)| key.0 < next_ring_distance) {
+                break;
+            }
+        }
+
+        nearest.map(|(_, position)| position)
+    }
+}
+
+// The bucket `offset` buckets along from `bucket`, out of `num_buckets`. On an axis that wraps each
+// bucket is only reached from one offset, so no bucket is searched twice.
+fn bucket_at_offset(bucket: usize, offset: isize, num_buckets: usize, wraps: bool) -> Option<usize> {
+    let num_buckets = num_buckets as isize;
+    let bucket = bucket as isize + offset;
+    if wraps {
+        let lowest_offset = -(num_buckets - 1) / 2;
+        (offset >= lowest_offset && offset < lowest_offset + num_buckets)
+            .then(|| bucket.rem_euclid(num_buckets) as usize)
+    } else {
+        (0..num_buckets).contains(&bucket).then_some(bucket as usize)
+    }
+}
+
+/// Splits the field into square buckets and heads for the bucket with the most taggable players,
+/// going after the closest player in that bucket. The idea is that a crowd is easier to catch
+/// someone in than a single player running away. Buckets are counted once at the start of every
+/// turn.
+#[derive(Clone, Debug)]
+pub struct DensestClusterPursuit {
+    pub bucket_size: usize,
+    // Every bucket with a player worth chasing in it at the start of the turn, densest first.
+    densest_buckets: Vec<(usize, usize)>
+}
+
+impl Default for DensestClusterPursuit {
+    fn default() -> Self {
+        DensestClusterPursuit {
+            bucket_size: 8,
+            densest_buckets: Vec::new()
+        }
+    }
+}
+
+impl MovementStrategy for DensestClusterPursuit {
+    fn choose_actions(&self, field_of_play: &FieldOfPlay, player: &Player, rng: &mut SimRng) -> Vec<Action> {
+        match self.find_cluster_target(field_of_play, player) {
+            Some(target) => pursue(field_of_play, player, target, rng),
+            None => RandomAvoidanceStrategy.choose_actions(field_of_play, player, rng)
+        }
+    }
+
+    fn start_turn(&mut self, field_of_play: &FieldOfPlay) {
+        let bucket_size = self.bucket_size.max(1);
+        let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        find_targets(field_of_play).into_iter().for_each(|(_, (x, y))| {
+            *counts.entry((x / bucket_size, y / bucket_size)).or_default() += 1;
+        });
+        let mut buckets: Vec<(usize, (usize, usize))> =
+            counts.into_iter().map(|(bucket, count)| (count, bucket)).collect();
+        // Ties between equally dense buckets go to the last one, the same way every run.
+        buckets.sort_unstable_by(|a, b| b.cmp(a));
+        self.densest_buckets = buckets.into_iter().map(|(_, bucket)| bucket).collect();
+    }
+
+    fn name(&self) -> &str {
+        "cluster"
+    }
+}
+
+impl DensestClusterPursuit {
+    // Players may have moved, been tagged or been frozen since the buckets were counted, so the
+    // positions in each bucket are checked as they are now. Buckets left with nobody this chaser
+    // can tag are skipped.
+    fn find_cluster_target(&self, field_of_play: &FieldOfPlay, player: &Player) -> Option<(usize, usize)> {
+        let bucket_size = self.bucket_size.max(1);
+        let location = player.get_location();
+        self.densest_buckets.iter().find_map(|(bucket_x, bucket_y)| {
+            let x_range = bucket_x * bucket_size..((bucket_x + 1) * bucket_size).min(field_of_play.x_len());
+            let y_range = bucket_y * bucket_size..((bucket_y + 1) * bucket_size).min(field_of_play.y_len());
+            y_range
+                .flat_map(|y| x_range.clone().map(move |x| (x, y)))
+                .filter(|(x, y)| {
+                    field_of_play
+                        .get_occupant(*x, *y)
+                        .is_some_and(|index| is_target(field_of_play, player, index))
+                })
+                .min_by_key(|position| distance_key(field_of_play, location, *position))
+        })
+    }
+}
+
+/// Searches for the shortest route around other players to a position next to a taggable player
+/// and takes the first step along it. Falls back to greedy pursuit if no route is found within
+/// `max_nodes` searched positions.
+#[derive(Clone, Debug)]
+pub struct PathfindingPursuit {
+    pub max_nodes: usize,
+    nearest: NearestPlayerPursuit
+}
+
+impl Default for PathfindingPursuit {
+    fn default() -> Self {
+        PathfindingPursuit {
+            max_nodes: 100_000,
+            nearest: NearestPlayerPursuit::default()
+        }
+    }
+}
+
+impl MovementStrategy for PathfindingPursuit {
+    fn choose_actions(&self, field_of_play: &FieldOfPlay, player: &Player, rng: &mut SimRng) -> Vec<Action> {
+        match self.find_first_step(field_of_play, player) {
+            Some((x, y)) => vec![Action::new_move(x, y)],
+            None => self.nearest.choose_actions(field_of_play, player, rng)
+        }
+    }
+
+    fn start_turn(&mut self, field_of_play: &FieldOfPlay) {
+        self.nearest.start_turn(field_of_play);
+    }
+
+    fn name(&self) -> &str {
+        "pathfinding"
+    }
+}
+
+impl PathfindingPursuit {
+    // Breadth first search over empty positions. Each queued position remembers the first step taken
+    // from the start so we do not have to rebuild the path once a target is found.
+    fn find_first_step(&self, field_of_play: &FieldOfPlay, player: &Player) -> Option<(usize, usize)> {
+        let start = player.get_location();
+        if is_next_to_target(field_of_play, player, start) {
+            return Some(start);
+        }
+
+        let mut visited: HashSet<(usize, usize)> = HashSet::new();
+        visited.insert(start);
+        let mut queue: VecDeque<((usize, usize), (usize, usize))> = VecDeque::new();
+        empty_neighbours(field_of_play, start).into_iter().for_each(|position| {
+            visited.insert(position);
+            queue.push_back((position, position));
+        });
+
+        while let Some((position, first_step)) = queue.pop_front() {
+            if is_next_to_target(field_of_play, player, position) {
+                return Some(first_step);
+            }
+            if visited.len() >= self.max_nodes {
+                break;
+            }
+            empty_neighbours(field_of_play, position)
+                .into_iter()
+                .filter(|neighbour| visited.insert(*neighbour))
+                .for_each(|neighbour| queue.push_back((neighbour, first_step)));
+        }
+
+        None
+    }
+}
+
+// Every player on the field who is neither it nor frozen, with their position. Whether a particular
+// chaser may tag them is left to [is_target].
+fn find_targets(field_of_play: &FieldOfPlay) -> Vec<(usize, (usize, usize))> {
+    field_of_play
+        .get_player_positions()
+        .into_iter()
+        .filter(|(index, _)| !field_of_play.is_it(*index) && !field_of_play.is_frozen(*index))
+        .collect()
+}
+
+// A player is worth chasing if the chaser is allowed to tag them and they are not already it or
+// frozen.
+fn is_target(field_of_play: &FieldOfPlay, player: &Player, index: usize) -> bool {
+    index != player.get_index()
+        && player.can_tag(index)
+        && !field_of_play.is_it(index)
+        && !field_of_play.is_frozen(index)
+}
+
+fn is_next_to_target(field_of_play: &FieldOfPlay, player: &Player, (x, y): (usize, usize)) -> bool {
+    field_of_play
+        .get_adjacent_player_indices(x, y)
+        .into_iter()
+        .any(|index| is_target(field_of_play, player, index))
+}
+
+fn empty_neighbours(field_of_play: &FieldOfPlay, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+    Direction::ALL
+        .iter()
+        .filter(|direction| field_of_play.is_position_valid_and_empty(**direction, x, y))
+        .filter_map(|direction| field_of_play.get_position_in_direction(*direction, x, y))
+        .collect()
+}
+
+// Players can move diagonally, so the number of moves between two positions is the larger of the x
+// and y differences. Straight line distance is used to break ties so the chaser lines up with the
+// target rather than wandering along the edge of a ring.
+fn distance_key(field_of_play: &FieldOfPlay, from: (usize, usize), to: (usize, usize)) -> (usize, usize) {
+    let (x_diff, y_diff) = field_of_play.get_axis_distances(from, to);
+
+    (x_diff.max(y_diff), x_diff.pow(2) + y_diff.pow(2))
+}
+
+// Searches outwards from the player one ring at a time, up to `max_radius` positions away, so nearby
+// targets are found without walking the whole field. On a wrapping field a ring can run off one
+// edge and back on at the other.
+fn find_nearest_target(field_of_play: &FieldOfPlay, player: &Player, max_radius: usize) -> Option<(usize, usize)> {
+    let (x, y) = player.get_location();
+    let y_len = field_of_play.y_len() as isize;
+    let x_len = field_of_play.x_len() as isize;
+    let max_radius = x_len.max(y_len).min(max_radius as isize);
+    for radius in 1..=max_radius {
+        let mut nearest: Option<(usize, usize)> = None;
+        for y_offset in -radius..=radius {
+            let is_edge_row = y_offset.abs() == radius;
+            let x_offsets: Vec<isize> = if is_edge_row {
+                (-radius..=radius).collect()
+            } else {
+                vec![-radius, radius]
+            };
+            for x_offset in x_offsets {
+                let position = match field_of_play.get_position_at_offset(x, y, (x_offset, y_offset)) {
+                    Some(position) => position,
+                    None => continue
+                };
+                if let Some(index) = field_of_play.get_occupant(position.0, position.1) {
+                    let location = player.get_location();
+                    let is_closer = nearest.is_none_or(|best| {
+                        distance_key(field_of_play, location, position) < distance_key(field_of_play, location, best)
+                    });
+                    if is_target(field_of_play, player, index) && is_closer {
+                        nearest = Some(position);
+                    }
+                }
+            }
+        }
+        if nearest.is_some() {
+            return nearest;
+        }
+    }
+
+    None
+}
+
+// Takes the empty adjacent position that gets closest to the target. If no move gets closer the
+// chaser waits when already next to the target, otherwise it takes a random step to get unstuck.
+fn pursue(field_of_play: &FieldOfPlay, player: &Player, target: (usize, usize), rng: &mut SimRng) -> Vec<Action> {
+    let location = player.get_location();
+    let current_distance = distance_key(field_of_play, location, target);
+    let best_step = empty_neighbours(field_of_play, location)
+        .into_iter()
+        .map(|position| (distance_key(field_of_play, position, target), position))
+        .filter(|(distance, _)| *distance < current_distance)
+        .min()
+        .map(|(_, position)| position);
+
+    let next_position = match best_step {
+        Some(position) => Some(position),
+        None if current_distance.0 <= 1 => Some(location),
+        None => empty_neighbours(field_of_play, location).choose(rng).copied()
+    };
+
+    next_position
+        .map(|(x, y)| vec![Action::new_move(x, y)])
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+use super::rng::new_rng;
+#[cfg(test)]
+use super::topology::Topology;
+#[cfg(test)]
+use rand::Rng;
+
+#[cfg(test)]
+fn place_it_player(field_of_play: &mut FieldOfPlay, x: usize, y: usize) -> Player {
+    let mut it_player = Player::new(0, true, field_of_play, &mut new_rng(0));
+    let (old_x, old_y) = it_player.get_location();
+    field_of_play.set_occupant(old_x, old_y, None);
+    field_of_play.set_occupant(x, y, Some(0));
+    it_player.set_location(x, y);
+
+    it_player
+}
+
+#[test]
+fn chaser_strategy_from_str_test() {
+    assert_eq!("nearest".parse::<ChaserStrategy>(), Ok(ChaserStrategy::Nearest));
+    assert_eq!("pathfinding".parse::<ChaserStrategy>(), Ok(ChaserStrategy::Pathfinding));
+    assert!("sideways".parse::<ChaserStrategy>().is_err());
+    ChaserStrategy::NAMES.iter().for_each(|name| {
+        assert_eq!(name.parse::<ChaserStrategy>().unwrap().to_string(), *name);
+    });
+}
+
+#[test]
+fn nearest_pursuit_moves_towards_closest_player_test() {
+    let mut field_of_play = FieldOfPlay::new(10, 10);
+    let it_player = place_it_player(&mut field_of_play, 5, 5);
+    field_of_play.set_occupant(8, 5, Some(1));
+    field_of_play.set_occupant(0, 0, Some(2));
+
+    let actions = NearestPlayerPursuit::default().choose_actions(&field_of_play, &it_player, &mut new_rng(0));
+
+    assert_eq!(actions[0].x_coordinate, Some(6));
+    assert_eq!(actions[0].y_coordinate, Some(5));
+}
+
+#[test]
+fn nearest_pursuit_ignores_tag_back_test() {
+    let mut field_of_play = FieldOfPlay::new(10, 10);
+    let mut it_player = place_it_player(&mut field_of_play, 5, 5);
+    it_player.become_it(1, 0, None);
+    field_of_play.set_occupant(7, 5, Some(1));
+    field_of_play.set_occupant(1, 5, Some(2));
+
+    assert_eq!(
+        NearestPlayerPursuit::default().find_target(&field_of_play, &it_player),
+        Some((1, 5))
+    );
+}
+
+#[test]
+fn cluster_pursuit_targets_densest_bucket_test() {
+    let mut field_of_play = FieldOfPlay::new(16, 16);
+    let it_player = place_it_player(&mut field_of_play, 7, 7);
+    // A lone player close by and a crowd further away.
+    field_of_play.set_occupant(9, 7, Some(1));
+    field_of_play.set_occupant(14, 14, Some(2));
+    field_of_play.set_occupant(15, 14, Some(3));
+    field_of_play.set_occupant(14, 15, Some(4));
+
+    let mut cluster_pursuit = DensestClusterPursuit::default();
+    cluster_pursuit.start_turn(&field_of_play);
+
+    assert_eq!(
+        cluster_pursuit.find_cluster_target(&field_of_play, &it_player),
+        Some((14, 14))
+    );
+    // Someone who left the crowd after the buckets were counted is not chased.
+    field_of_play.set_occupant(14, 14, None);
+    field_of_play.set_occupant(7, 14, Some(2));
+    assert_eq!(
+        cluster_pursuit.find_cluster_target(&field_of_play, &it_player),
+        Some((15, 14))
+    );
+}
+
+#[test]
+fn nearest_pursuit_finds_distant_players_from_start_of_turn_test() {
+    let mut field_of_play = FieldOfPlay::new(40, 1);
+    let it_player = place_it_player(&mut field_of_play, 0, 0);
+    field_of_play.set_occupant(30, 0, Some(1));
+    let mut nearest_pursuit = NearestPlayerPursuit {
+        search_radius: 4,
+        ..NearestPlayerPursuit::default()
+    };
+
+    assert_eq!(nearest_pursuit.find_target(&field_of_play, &it_player), None);
+    nearest_pursuit.start_turn(&field_of_play);
+    assert_eq!(nearest_pursuit.find_target(&field_of_play, &it_player), Some((30, 0)));
+    // Closer players are still found as they are now.
+    field_of_play.set_occupant(3, 0, Some(2));
+    assert_eq!(nearest_pursuit.find_target(&field_of_play, &it_player), Some((3, 0)));
+}
+
+#[test]
+fn target_grid_finds_nearest_target_test() {
+    let mut rng = new_rng(0);
+    [Topology::Bounded, Topology::Torus].iter().for_each(|topology| {
+        // Sizes that do not divide into buckets, so the last bucket on each axis is smaller.
+        let mut field_of_play = FieldOfPlay::new(37, 23);
+        field_of_play.set_topology(*topology);
+        let it_player = place_it_player(&mut field_of_play, 0, 0);
+        (1..6).for_each(|index| {
+            let (x, y) = (rng.gen_range(0..37), rng.gen_range(0..23));
+            if field_of_play.get_occupant(x, y).is_none() {
+                field_of_play.set_occupant(x, y, Some(index));
+            }
+        });
+        let targets = find_targets(&field_of_play);
+        [1, 4, 16].iter().for_each(|bucket_size| {
+            let target_grid = TargetGrid::new(&field_of_play, *bucket_size);
+            (0..23).for_each(|y| {
+                (0..37).for_each(|x| {
+                    let mut chaser = it_player.clone();
+                    chaser.set_location(x, y);
+                    let distance = |position: (usize, usize)| distance_key(&field_of_play, (x, y), position);
+                    let expected = targets
+                        .iter()
+                        .filter(|(index, _)| *index != 0)
+                        .map(|(_, position)| distance(*position))
+                        .min();
+                    let found = target_grid.find_nearest(&field_of_play, &chaser);
+                    assert_eq!(found.map(distance), expected);
+                });
+            });
+        });
+    });
+}
+
+#[test]
+fn pathfinding_pursuit_routes_around_players_test() {
+    // A wall of players between the chaser and their target with a gap at the bottom. Every player in
+    // the wall tagged the chaser so none of them can be tagged.
+    let mut field_of_play = FieldOfPlay::new(7, 5);
+    let mut it_player = place_it_player(&mut field_of_play, 1, 0);
+    it_player.become_it(1, 0, None);
+    (0..4).for_each(|y| field_of_play.set_occupant(3, y, Some(1)));
+    field_of_play.set_occupant(6, 0, Some(2));
+
+    let first_step = PathfindingPursuit::default().find_first_step(&field_of_play, &it_player);
+
+    assert_eq!(first_step, Some((1, 1)));
+}
+
+#[test]
+fn nearest_pursuit_wraps_around_torus_test() {
+    let mut field_of_play = FieldOfPlay::new(10, 10);
+    field_of_play.set_topology(Topology::Torus);
+    let it_player = place_it_player(&mut field_of_play, 1, 5);
+    field_of_play.set_occupant(8, 5, Some(1));
+    field_of_play.set_occupant(5, 5, Some(2));
+
+    assert_eq!(
+        NearestPlayerPursuit::default().find_target(&field_of_play, &it_player),
+        Some((8, 5))
+    );
+    let actions = NearestPlayerPursuit::default().choose_actions(&field_of_play, &it_player, &mut new_rng(0));
+    assert_eq!(actions[0].x_coordinate, Some(0));
+    assert_eq!(actions[0].y_coordinate, Some(5));
+}
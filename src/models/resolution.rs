@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the actions of the players in a turn are carried out.
+/// - Sequential: players act one at a time in turn order and each sees the moves of the players who acted before them.
+/// - Simultaneous: every player decides what to do looking at the field as it was at the start of the turn. Tags and
+///   rescues are then carried out in turn order, followed by every move at once, with any moves that collide settled by
+///   a [CollisionRule].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MoveResolution {
+    #[default]
+    Sequential,
+    Simultaneous
+}
+
+impl MoveResolution {
+    pub const NAMES: [&'static str; 2] = ["sequential", "simultaneous"];
+}
+
+impl FromStr for MoveResolution {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "sequential" => Ok(MoveResolution::Sequential),
+            "simultaneous" => Ok(MoveResolution::Simultaneous),
+            _ => Err(format!(
+                "unknown move resolution: {}. Must be one of: {}",
+                name,
+                MoveResolution::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for MoveResolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MoveResolution::Sequential => "sequential",
+            MoveResolution::Simultaneous => "simultaneous"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Who gets to move when simultaneous moves collide. Players who do not get to move stay where they
+/// were.
+/// - RandomPriority: one of the players, picked at random, moves.
+/// - Bounce: nobody moves.
+/// - RiskTolerance: the player with the highest risk tolerance moves, the lowest index if tied.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CollisionRule {
+    #[default]
+    RandomPriority,
+    Bounce,
+    RiskTolerance
+}
+
+impl CollisionRule {
+    pub const NAMES: [&'static str; 3] = ["random-priority", "bounce", "risk-tolerance"];
+}
+
+impl FromStr for CollisionRule {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "random-priority" => Ok(CollisionRule::RandomPriority),
+            "bounce" => Ok(CollisionRule::Bounce),
+            "risk-tolerance" => Ok(CollisionRule::RiskTolerance),
+            _ => Err(format!(
+                "unknown collision rule: {}. Must be one of: {}",
+                name,
+                CollisionRule::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for CollisionRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CollisionRule::RandomPriority => "random-priority",
+            CollisionRule::Bounce => "bounce",
+            CollisionRule::RiskTolerance => "risk-tolerance"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The ways simultaneous moves can collide.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Conflict {
+    /// Two or more players moving to the same position.
+    SameCell,
+    /// Two players moving through each other, either by swapping positions or by crossing
+    /// diagonally.
+    Swap
+}
+
+/// True if moving one player from `a` to `a_to` and another from `b` to `b_to` at the same time
+/// would take them through each other. Both moves must be to an adjacent position.
+pub fn is_swap(a: (usize, usize), a_to: (usize, usize), b: (usize, usize), b_to: (usize, usize)) -> bool {
+    if a == a_to || b == b_to {
+        return false;
+    }
+    let swapped = a_to == b && b_to == a;
+    // Two players side by side in a row both moving diagonally into the other's column of the
+    // next row, or the same for two players in a column.
+    let crossed_in_row =
+        a.1 == b.1 && a.0 != b.0 && a_to.1 == b_to.1 && a_to.1 != a.1 && a_to.0 == b.0 && b_to.0 == a.0;
+    let crossed_in_column =
+        a.0 == b.0 && a.1 != b.1 && a_to.0 == b_to.0 && a_to.0 != a.0 && a_to.1 == b.1 && b_to.1 == a.1;
+
+    swapped || crossed_in_row || crossed_in_column
+}
+
+#[test]
+fn resolution_from_str_test() {
+    MoveResolution::NAMES.iter().for_each(|name| {
+        assert_eq!(name.parse::<MoveResolution>().unwrap().to_string(), *name);
+    });
+    CollisionRule::NAMES.iter().for_each(|name| {
+        assert_eq!(name.parse::<CollisionRule>().unwrap().to_string(), *name);
+    });
+    assert!("eventually".parse::<MoveResolution>().is_err());
+    assert!("coin-toss".parse::<CollisionRule>().is_err());
+}
+
+#[test]
+fn is_swap_test() {
+    assert!(is_swap((1, 1), (2, 1), (2, 1), (1, 1)));
+    // Crossing diagonally, in a row and in a column.
+    assert!(is_swap((1, 1), (2, 2), (2, 1), (1, 2)));
+    assert!(is_swap((1, 1), (2, 2), (1, 2), (2, 1)));
+    // Moving side by side, or one player staying put.
+    assert!(!is_swap((1, 1), (1, 2), (2, 1), (2, 2)));
+    assert!(!is_swap((1, 1), (2, 2), (2, 1), (2, 1)));
+}
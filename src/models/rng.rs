@@ -0,0 +1,24 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generator used for every random decision in a simulation. We use ChaCha
+/// instead of `StdRng` because its output is guaranteed to be stable across releases and
+/// platforms, so a seed recorded in a bug report will keep reproducing the same game.
+pub type SimRng = ChaCha8Rng;
+
+pub fn new_rng(seed: u64) -> SimRng {
+    SimRng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+use rand::Rng;
+
+#[test]
+fn new_rng_is_reproducible_test() {
+    let mut first = new_rng(42);
+    let mut second = new_rng(42);
+    let first_draws: Vec<u32> = (0..10).map(|_| first.gen()).collect();
+    let second_draws: Vec<u32> = (0..10).map(|_| second.gen()).collect();
+
+    assert_eq!(first_draws, second_draws);
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The rule set a game is played with.
+/// - Classic: tagging someone makes them it and the tagger is no longer it.
+/// - Freeze: it players stay it and tagged players freeze in place until another player who is not frozen rescues them
+///   by standing next to them. The game ends when everyone is frozen.
+/// - Infection: it players stay it and tagged players join them. The game ends when everyone is it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Freeze,
+    Infection
+}
+
+impl GameMode {
+    pub const NAMES: [&'static str; 3] = ["classic", "freeze", "infection"];
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "classic" => Ok(GameMode::Classic),
+            "freeze" => Ok(GameMode::Freeze),
+            "infection" => Ok(GameMode::Infection),
+            _ => Err(format!(
+                "unknown game mode: {}. Must be one of: {}",
+                name,
+                GameMode::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GameMode::Classic => "classic",
+            GameMode::Freeze => "freeze",
+            GameMode::Infection => "infection"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[test]
+fn game_mode_from_str_test() {
+    assert_eq!("freeze".parse::<GameMode>(), Ok(GameMode::Freeze));
+    assert!("hide-and-seek".parse::<GameMode>().is_err());
+    GameMode::NAMES.iter().for_each(|name| {
+        assert_eq!(name.parse::<GameMode>().unwrap().to_string(), *name);
+    });
+}
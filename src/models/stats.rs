@@ -1,32 +1,201 @@
+use super::heatmap::{Heatmap, HeatmapKind};
 use super::player::Player;
-use std::collections::HashMap;
+use super::resolution::{CollisionRule, Conflict, MoveResolution};
+use super::rules::GameMode;
+use super::tag_network::TagNetwork;
+use super::turn_order::TurnOrder;
+use super::vision::Vision;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
 
 static GENERIC_VEC_ACCESS_PANIC_ERR_MSG: &str = "Invalid player name key for stats.";
+// Any two points are on a straight line, so a correlation needs at least three to mean anything.
+const MIN_CORRELATION_PAIRS: usize = 3;
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Stats {
     field_x_len: usize,
     field_y_len: usize,
     number_of_turns: usize,
-    player_stats: HashMap<String, PlayerStats>
+    turns_played: usize,
+    game_mode: GameMode,
+    chaser_strategy: String,
+    turn_order: TurnOrder,
+    // The number of tags made by the player acting first in a turn, second and so on.
+    tags_by_turn_position: Vec<usize>,
+    move_resolution: MoveResolution,
+    collision_rule: CollisionRule,
+    // Simultaneous moves only. The number of times players tried to move to the same position, and
+    // through each other.
+    same_cell_conflicts: usize,
+    swap_conflicts: usize,
+    vision: Option<Vision>,
+    // The turn number of every tag, in order.
+    tag_turns: Vec<usize>,
+    // Freeze tag only. The turn on which every player who is not it was frozen, if that happened.
+    all_frozen_turn: Option<usize>,
+    // The number of it players at the end of each turn. In infection this is the infection curve.
+    it_players_per_turn: Vec<usize>,
+    // Infection only. The turn on which every player became it, if that happened.
+    all_infected_turn: Option<usize>,
+    // A BTreeMap keeps the output order stable so two runs with the same seed print identical stats.
+    player_stats: BTreeMap<String, PlayerStats>,
+    occupancy_heatmap: Heatmap,
+    it_heatmap: Heatmap,
+    tag_heatmap: Heatmap,
+    tag_network: TagNetwork
 }
 
-#[derive(Debug)]
-struct PlayerStats {
-    risk_tolerance: f64,
-    rounds_started_as_it: usize,
-    rounds_made_it: usize
+/// The statistics for a single player.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerStats {
+    pub index: usize,
+    pub risk_tolerance: f64,
+    pub rounds_started_as_it: usize,
+    pub rounds_made_it: usize,
+    /// Freeze tag only.
+    pub times_frozen: usize,
+    /// Freeze tag only. The number of frozen players this player unfroze.
+    pub rescues: usize,
+    /// The number of positions moved over the whole game.
+    pub distance_moved: usize,
+    /// The number of turns the player could have moved but stayed where they were. Players stopped
+    /// by a collision tried to move, so they are counted in `conflicts_lost` instead.
+    pub turns_stuck: usize,
+    /// The most turns in a row the player started as it.
+    pub longest_it_streak: usize,
+    pub tags_given: usize,
+    pub tags_received: usize,
+    /// Simultaneous moves only. The number of times the player was stopped from moving by a
+    /// collision with another player.
+    pub conflicts_lost: usize,
+    /// The most positions the player could move in a turn.
+    pub max_speed: usize,
+    pub max_stamina: usize,
+    /// The stamina used moving more than one position a turn, and got back by standing still.
+    pub stamina_spent: usize,
+    pub stamina_recovered: usize,
+    /// Vision only. The number of turns the player started with an it player in sight.
+    pub it_sightings: usize,
+    it_streak: usize,
+    // The turn the player became it or last tagged someone, whichever was later. None while they
+    // are not it.
+    it_since_turn: Option<usize>,
+    turns_to_tag: Vec<usize>,
+    distance_from_it_total: usize,
+    turns_near_it: usize,
+    // Where the player acted in the current turn, 0 being first.
+    turn_position: usize,
+    turn_position_total: usize,
+    turns_acted: usize
+}
+
+/// How far a player moved in a turn, 0 if they were stuck, and their stamina before and after.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MoveRecord {
+    pub distance: usize,
+    pub stamina_before: usize,
+    pub stamina_after: usize,
+    /// Simultaneous moves only. True if a collision with another player stopped the move.
+    pub collided: bool
+}
+
+impl PlayerStats {
+    /// The average number of turns it took the player to tag someone after becoming it, or after
+    /// their last tag if they stayed it. None if they never tagged anyone.
+    pub fn mean_turns_to_tag(&self) -> Option<f64> {
+        mean(&self.turns_to_tag)
+    }
+
+    /// The average distance to the closest it player at the end of every turn the player was not it.
+    /// Distance is the number of moves it would take to get there, ignoring obstacles. None if the
+    /// player was it for the whole game.
+    pub fn mean_distance_from_it(&self) -> Option<f64> {
+        if self.turns_near_it == 0 {
+            return None;
+        }
+
+        Some(self.distance_from_it_total as f64 / self.turns_near_it as f64)
+    }
+
+    /// Where in the turn the player acted on average, 0 being first. None if no turns were played.
+    pub fn mean_turn_position(&self) -> Option<f64> {
+        if self.turns_acted == 0 {
+            return None;
+        }
+
+        Some(self.turn_position_total as f64 / self.turns_acted as f64)
+    }
+
+    fn start_turn(&mut self, is_it: bool, turn_position: usize) {
+        if is_it {
+            self.rounds_started_as_it += 1;
+            self.it_streak += 1;
+            self.longest_it_streak = self.longest_it_streak.max(self.it_streak);
+        } else {
+            self.it_streak = 0;
+        }
+        self.turn_position = turn_position;
+        self.turn_position_total += turn_position;
+        self.turns_acted += 1;
+    }
+
+    fn add_move(&mut self, record: MoveRecord) {
+        self.distance_moved += record.distance;
+        if record.distance == 0 && !record.collided {
+            self.turns_stuck += 1;
+        }
+        self.stamina_spent += record.stamina_before.saturating_sub(record.stamina_after);
+        self.stamina_recovered += record.stamina_after.saturating_sub(record.stamina_before);
+    }
+
+    // Every metric that is compared with risk tolerance, by name.
+    fn metrics(&self) -> [(&'static str, Option<f64>); 7] {
+        [
+            ("distance_moved", Some(self.distance_moved as f64)),
+            ("turns_stuck", Some(self.turns_stuck as f64)),
+            ("longest_it_streak", Some(self.longest_it_streak as f64)),
+            ("mean_turns_to_tag", self.mean_turns_to_tag()),
+            ("tags_given", Some(self.tags_given as f64)),
+            ("tags_received", Some(self.tags_received as f64)),
+            ("mean_distance_from_it", self.mean_distance_from_it())
+        ]
+    }
 }
 
 impl Stats {
-    pub fn new(players: &Vec<Player>, number_of_turns: usize, field_x_len: usize, field_y_len: usize) -> Self {
-        let mut player_stats = HashMap::new();
+    pub fn new(players: &[Player], number_of_turns: usize, field_x_len: usize, field_y_len: usize) -> Self {
+        let mut player_stats = BTreeMap::new();
         players.iter().for_each(|player| {
             player_stats.insert(
                 player.name.to_owned(),
                 PlayerStats {
+                    index: player.get_index(),
                     risk_tolerance: player.get_risk_tolerance(),
                     rounds_started_as_it: 0,
-                    rounds_made_it: 0
+                    rounds_made_it: 0,
+                    times_frozen: 0,
+                    rescues: 0,
+                    distance_moved: 0,
+                    turns_stuck: 0,
+                    longest_it_streak: 0,
+                    tags_given: 0,
+                    tags_received: 0,
+                    conflicts_lost: 0,
+                    max_speed: player.get_max_speed(),
+                    max_stamina: player.get_max_stamina(),
+                    stamina_spent: 0,
+                    stamina_recovered: 0,
+                    it_sightings: 0,
+                    it_streak: 0,
+                    it_since_turn: if player.is_it { Some(0) } else { None },
+                    turns_to_tag: Vec::new(),
+                    distance_from_it_total: 0,
+                    turns_near_it: 0,
+                    turn_position: 0,
+                    turn_position_total: 0,
+                    turns_acted: 0
                 }
             );
         });
@@ -35,38 +204,682 @@ impl Stats {
             field_x_len,
             field_y_len,
             player_stats,
-            number_of_turns
+            number_of_turns,
+            turns_played: 0,
+            game_mode: GameMode::default(),
+            chaser_strategy: String::new(),
+            turn_order: TurnOrder::default(),
+            tags_by_turn_position: vec![0; players.len()],
+            move_resolution: MoveResolution::default(),
+            collision_rule: CollisionRule::default(),
+            same_cell_conflicts: 0,
+            swap_conflicts: 0,
+            vision: None,
+            tag_turns: Vec::new(),
+            all_frozen_turn: None,
+            it_players_per_turn: Vec::new(),
+            all_infected_turn: None,
+            occupancy_heatmap: Heatmap::new(field_x_len, field_y_len),
+            it_heatmap: Heatmap::new(field_x_len, field_y_len),
+            tag_heatmap: Heatmap::new(field_x_len, field_y_len),
+            tag_network: TagNetwork::new(players.len())
         }
     }
 
-    pub fn record_start_player_details(&mut self, player: &Player) {
+    pub fn set_game_mode(&mut self, game_mode: GameMode) {
+        self.game_mode = game_mode;
+    }
+
+    pub fn set_turn_order(&mut self, turn_order: TurnOrder) {
+        self.turn_order = turn_order;
+    }
+
+    pub fn turn_order(&self) -> TurnOrder {
+        self.turn_order
+    }
+
+    /// The number of tags made by the player who acted first in their turn, then second and so on.
+    /// With a fixed turn order the first player to act is always p0.
+    pub fn tags_by_turn_position(&self) -> &[usize] {
+        &self.tags_by_turn_position
+    }
+
+    pub fn set_move_resolution(&mut self, move_resolution: MoveResolution, collision_rule: CollisionRule) {
+        self.move_resolution = move_resolution;
+        self.collision_rule = collision_rule;
+    }
+
+    pub fn move_resolution(&self) -> MoveResolution {
+        self.move_resolution
+    }
+
+    pub fn set_vision(&mut self, vision: Option<Vision>) {
+        self.vision = vision;
+    }
+
+    /// What players could see, or None if they knew where the it player was at all times.
+    pub fn vision(&self) -> Option<Vision> {
+        self.vision
+    }
+
+    pub fn collision_rule(&self) -> CollisionRule {
+        self.collision_rule
+    }
+
+    /// Records simultaneous moves colliding, stopping the players named in `losers` from moving.
+    pub fn record_conflict(&mut self, conflict: Conflict, losers: &[String]) {
+        match conflict {
+            Conflict::SameCell => self.same_cell_conflicts += 1,
+            Conflict::Swap => self.swap_conflicts += 1
+        }
+        for loser in losers {
+            self.player_stats
+                .get_mut(loser)
+                .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG)
+                .conflicts_lost += 1;
+        }
+    }
+
+    /// The number of times two or more players tried to move to the same position at once.
+    pub fn same_cell_conflicts(&self) -> usize {
+        self.same_cell_conflicts
+    }
+
+    /// The number of times two players tried to move through each other at once.
+    pub fn swap_conflicts(&self) -> usize {
+        self.swap_conflicts
+    }
+
+    pub fn number_of_turns(&self) -> usize {
+        self.number_of_turns
+    }
+
+    /// The length of the x-axis and y-axis of the field.
+    pub fn field_size(&self) -> (usize, usize) {
+        (self.field_x_len, self.field_y_len)
+    }
+
+    pub fn game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    pub fn chaser_strategy(&self) -> &str {
+        &self.chaser_strategy
+    }
+
+    /// The statistics for every player keyed by their name, in name order.
+    pub fn player_stats(&self) -> &BTreeMap<String, PlayerStats> {
+        &self.player_stats
+    }
+
+    /// Records the name of the strategy used by the it player so runs with different strategies can
+    /// be compared.
+    pub fn set_chaser_strategy(&mut self, name: &str) {
+        self.chaser_strategy = name.to_owned();
+    }
+
+    /// Records a player starting their turn `turn_position` in the turn order, 0 being first. Called
+    /// before the player acts.
+    pub fn record_start_player_details(&mut self, player: &Player, turn_position: usize) {
+        self.player_stats
+            .get_mut(&player.name)
+            .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG)
+            .start_turn(player.is_it, turn_position);
+    }
+
+    /// Records every player starting the turn at once, for when no player's turn can change another
+    /// player's before they act. `turn_positions` holds each player's place in the turn order, by
+    /// player index.
+    pub fn record_every_player_start(&mut self, players: &[Player], turn_positions: &[usize]) {
+        self.record_for_every_player(|player_stats| {
+            let index = player_stats.index;
+            player_stats.start_turn(players[index].is_it, turn_positions[index]);
+        });
+    }
+
+    /// Records a player moving, or getting stuck if they moved a distance of 0.
+    pub fn record_move(&mut self, name: &str, record: MoveRecord) {
+        self.player_stats
+            .get_mut(name)
+            .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG)
+            .add_move(record);
+    }
+
+    /// Records every player's move at once, by player index. Players without a move did not try to
+    /// move this turn.
+    pub fn record_every_player_move(&mut self, records: &[Option<MoveRecord>]) {
+        self.record_for_every_player(|player_stats| {
+            if let Some(record) = records[player_stats.index] {
+                player_stats.add_move(record);
+            }
+        });
+    }
+
+    /// Records a player starting their turn with an it player in sight.
+    pub fn record_it_sighting(&mut self, name: &str) {
+        let player_stats = self.player_stats.get_mut(name).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
+        player_stats.it_sightings += 1;
+    }
+
+    /// Records which players started the turn with an it player in sight, by player index.
+    pub fn record_every_player_sighting(&mut self, sightings: &[bool]) {
+        self.record_for_every_player(|player_stats| {
+            if sightings[player_stats.index] {
+                player_stats.it_sightings += 1;
+            }
+        });
+    }
+
+    // Runs `record` on the stats of every player across the threads of the current Rayon thread
+    // pool. Every player is recorded on their own, so the result is the same on any number of
+    // threads.
+    fn record_for_every_player<F>(&mut self, record: F)
+    where
+        F: Fn(&mut PlayerStats) + Send + Sync
+    {
+        self.player_stats
+            .par_iter_mut()
+            .for_each(|(_, player_stats)| record(player_stats));
+    }
+
+    /// The stamina every player used moving more than one position a turn.
+    pub fn stamina_spent(&self) -> usize {
+        self.player_stats
+            .values()
+            .map(|stats_for_player| stats_for_player.stamina_spent)
+            .sum()
+    }
+
+    /// True if any player can move more than one position a turn.
+    pub fn can_sprint(&self) -> bool {
+        self.player_stats
+            .values()
+            .any(|stats_for_player| stats_for_player.max_speed > 1)
+    }
+
+    /// Records `tagger_name` tagging `tagged_name`. The player who was tagged is recorded separately
+    /// by [Stats::record_new_it_details] or [Stats::record_frozen_details].
+    pub fn record_tag_given(&mut self, tagger_name: &str, tagged_name: &str, turn_num: usize) {
+        let tagged_index = self
+            .player_stats
+            .get(tagged_name)
+            .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG)
+            .index;
         let player_stats = self
             .player_stats
-            .get_mut(&player.name)
+            .get_mut(tagger_name)
             .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
-        if player.is_it {
-            player_stats.rounds_started_as_it += 1;
+        player_stats.tags_given += 1;
+        if let Some(it_since_turn) = player_stats.it_since_turn {
+            player_stats.turns_to_tag.push(turn_num - it_since_turn);
         }
+        // In classic the tagger stops being it. Otherwise they go on from this tag.
+        player_stats.it_since_turn = match self.game_mode {
+            GameMode::Classic => None,
+            GameMode::Freeze | GameMode::Infection => Some(turn_num)
+        };
+        self.tags_by_turn_position[player_stats.turn_position] += 1;
+        self.tag_network.record_tag(player_stats.index, tagged_index);
     }
 
-    pub fn record_new_it_details(&mut self, name: String) {
+    /// Who tagged whom over the game.
+    pub fn tag_network(&self) -> &TagNetwork {
+        &self.tag_network
+    }
+
+    /// Called at the end of every turn to add where every player is to the heatmaps.
+    pub fn record_positions(&mut self, players: &[Player]) {
+        for player in players {
+            let (x, y) = player.get_location();
+            self.occupancy_heatmap.increment(x, y);
+            if player.is_it {
+                self.it_heatmap.increment(x, y);
+            }
+        }
+    }
+
+    /// Records a player being tagged at (x, y).
+    pub fn record_tag_location(&mut self, x: usize, y: usize) {
+        self.tag_heatmap.increment(x, y);
+    }
+
+    pub fn heatmap(&self, kind: HeatmapKind) -> &Heatmap {
+        match kind {
+            HeatmapKind::Occupancy => &self.occupancy_heatmap,
+            HeatmapKind::It => &self.it_heatmap,
+            HeatmapKind::Tags => &self.tag_heatmap
+        }
+    }
+
+    /// Called at the end of every turn with the distance from every player to the closest it player,
+    /// by player index. None for it players, and when there are no it players.
+    pub fn record_distances_from_it(&mut self, distances: &[Option<usize>]) {
+        self.record_for_every_player(|player_stats| {
+            if let Some(distance) = distances[player_stats.index] {
+                player_stats.distance_from_it_total += distance;
+                player_stats.turns_near_it += 1;
+            }
+        });
+    }
+
+    pub fn record_new_it_details(&mut self, name: String, turn_num: usize) {
         let player_stats = self
             .player_stats
             .get_mut(&name)
             .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
         player_stats.rounds_made_it += 1;
+        player_stats.tags_received += 1;
+        player_stats.it_since_turn = Some(turn_num);
+        self.tag_turns.push(turn_num);
+    }
+
+    pub fn record_frozen_details(&mut self, name: &str, turn_num: usize) {
+        let player_stats = self.player_stats.get_mut(name).expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
+        player_stats.times_frozen += 1;
+        player_stats.tags_received += 1;
+        self.tag_turns.push(turn_num);
+    }
+
+    pub fn record_rescue_details(&mut self, rescuer_name: &str) {
+        let player_stats = self
+            .player_stats
+            .get_mut(rescuer_name)
+            .expect(GENERIC_VEC_ACCESS_PANIC_ERR_MSG);
+        player_stats.rescues += 1;
+    }
+
+    pub fn record_all_frozen(&mut self, turn_num: usize) {
+        self.all_frozen_turn = Some(turn_num);
+    }
+
+    /// Called at the end of every turn with the number of players who are it.
+    pub fn record_it_player_count(&mut self, it_player_count: usize) {
+        self.it_players_per_turn.push(it_player_count);
+    }
+
+    pub fn record_all_infected(&mut self, turn_num: usize) {
+        self.all_infected_turn = Some(turn_num);
+    }
+
+    /// The number of it players at the end of each turn, starting with the first turn.
+    pub fn it_players_per_turn(&self) -> &[usize] {
+        &self.it_players_per_turn
+    }
+
+    /// The turn on which every player became it. Always None outside of infection.
+    pub fn all_infected_turn(&self) -> Option<usize> {
+        self.all_infected_turn
+    }
+
+    /// Called at the end of every turn. Games can end before the turn limit, so this is the number
+    /// of turns actually played.
+    pub fn record_turn_played(&mut self, turn_num: usize) {
+        self.turns_played = turn_num;
+    }
+
+    pub fn turns_played(&self) -> usize {
+        self.turns_played
+    }
+
+    /// The turn on which every player who is not it was frozen. Always None outside of freeze tag.
+    pub fn all_frozen_turn(&self) -> Option<usize> {
+        self.all_frozen_turn
+    }
+
+    pub fn freeze_count(&self) -> usize {
+        self.player_stats
+            .values()
+            .map(|stats_for_player| stats_for_player.times_frozen)
+            .sum()
+    }
+
+    pub fn rescue_count(&self) -> usize {
+        self.player_stats
+            .values()
+            .map(|stats_for_player| stats_for_player.rescues)
+            .sum()
+    }
+
+    pub fn tag_count(&self) -> usize {
+        self.tag_turns.len()
+    }
+
+    /// The average number of tags per turn played.
+    pub fn tags_per_turn(&self) -> f64 {
+        if self.turns_played == 0 {
+            return 0.0;
+        }
+
+        self.tag_count() as f64 / self.turns_played as f64
+    }
+
+    /// The Pearson correlation between the risk tolerance of the players and each of the other per
+    /// player metrics, by metric name. Players without a value for a metric, such as a mean turns
+    /// to tag for a player who never tagged anyone, are left out of it. None if there are fewer than
+    /// three players to compare or either side does not vary.
+    pub fn risk_tolerance_correlations(&self) -> Vec<(&'static str, Option<f64>)> {
+        let metrics: Vec<_> = self
+            .player_stats
+            .values()
+            .map(|stats_for_player| (stats_for_player.risk_tolerance, stats_for_player.metrics()))
+            .collect();
+        let metric_names = metrics.first().map_or_else(Vec::new, |(_, player_metrics)| {
+            player_metrics.iter().map(|(name, _)| *name).collect()
+        });
+
+        metric_names
+            .into_iter()
+            .enumerate()
+            .map(|(metric_index, name)| {
+                let pairs: Vec<(f64, f64)> = metrics
+                    .iter()
+                    .filter_map(|(risk_tolerance, player_metrics)| {
+                        player_metrics[metric_index].1.map(|value| (*risk_tolerance, value))
+                    })
+                    .collect();
+
+                (name, pearson_correlation(&pairs))
+            })
+            .collect()
+    }
+
+    /// The average number of turns between tags, counting the first from the start of the game. None
+    /// if nobody was tagged.
+    pub fn mean_turns_between_tags(&self) -> Option<f64> {
+        let mut previous_tag_turn = 0;
+        let gaps: Vec<usize> = self
+            .tag_turns
+            .iter()
+            .map(|tag_turn| {
+                let gap = tag_turn - previous_tag_turn;
+                previous_tag_turn = *tag_turn;
+                gap
+            })
+            .collect();
+
+        mean(&gaps)
+    }
+}
+
+fn mean(values: &[usize]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<usize>() as f64 / values.len() as f64)
+}
+
+fn pearson_correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < MIN_CORRELATION_PAIRS {
+        return None;
+    }
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = pairs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance_x: f64 = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let variance_y: f64 = pairs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
     }
 
-    pub fn output_stats_about_players(&self) {
-        println!(
-            "{} players played for {} turns on a field {} by {} large",
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map_or_else(|| "n/a".to_owned(), |value| format!("{:.2}", value))
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} players played {} tag for {} of {} turns on a field {} by {} large",
             self.player_stats.keys().len(),
+            self.game_mode,
+            self.turns_played,
             self.number_of_turns,
             self.field_x_len,
             self.field_y_len
-        );
-        self.player_stats.iter().for_each(|(name, stats_for_player)| {
-            println!("{}: {:?}", name, stats_for_player);
-        });
+        )?;
+        writeln!(
+            f,
+            "chaser strategy: {}, tags: {}, tags per 100 turns: {:.2}, mean turns between tags: {}",
+            self.chaser_strategy,
+            self.tag_count(),
+            self.tags_per_turn() * 100.0,
+            format_optional(self.mean_turns_between_tags())
+        )?;
+        let tags_by_turn_position: Vec<String> =
+            self.tags_by_turn_position.iter().map(|tags| tags.to_string()).collect();
+        writeln!(
+            f,
+            "turn order: {}, tags by turn position: {}",
+            self.turn_order,
+            tags_by_turn_position.join(", ")
+        )?;
+        if self.move_resolution == MoveResolution::Simultaneous {
+            writeln!(
+                f,
+                "simultaneous moves with collision rule: {}, same cell conflicts: {}, swap conflicts: {}",
+                self.collision_rule, self.same_cell_conflicts, self.swap_conflicts
+            )?;
+        }
+        if self.can_sprint() {
+            writeln!(f, "stamina spent sprinting: {}", self.stamina_spent())?;
+        }
+        if let Some(vision) = self.vision {
+            writeln!(
+                f,
+                "vision range: {}, vision angle: {} degrees",
+                vision.range, vision.angle
+            )?;
+        }
+        if self.game_mode == GameMode::Freeze {
+            writeln!(
+                f,
+                "freezes: {}, rescues: {}, all frozen on turn: {}",
+                self.freeze_count(),
+                self.rescue_count(),
+                self.all_frozen_turn
+                    .map_or_else(|| "never".to_owned(), |turn| turn.to_string())
+            )?;
+        }
+        if self.game_mode == GameMode::Infection {
+            writeln!(
+                f,
+                "infections: {}, everyone infected on turn: {}",
+                self.tag_count(),
+                self.all_infected_turn
+                    .map_or_else(|| "never".to_owned(), |turn| turn.to_string())
+            )?;
+            let infection_curve: Vec<String> = self
+                .it_players_per_turn
+                .iter()
+                .map(|it_player_count| it_player_count.to_string())
+                .collect();
+            writeln!(f, "it players per turn: {}", infection_curve.join(", "))?;
+        }
+        for (name, stats_for_player) in self.player_stats.iter() {
+            write!(
+                f,
+                "{}: risk_tolerance: {}, rounds_started_as_it: {}, rounds_made_it: {}",
+                name,
+                stats_for_player.risk_tolerance,
+                stats_for_player.rounds_started_as_it,
+                stats_for_player.rounds_made_it
+            )?;
+            if self.game_mode == GameMode::Freeze {
+                write!(
+                    f,
+                    ", times_frozen: {}, rescues: {}",
+                    stats_for_player.times_frozen, stats_for_player.rescues
+                )?;
+            }
+            write!(
+                f,
+                ", distance_moved: {}, turns_stuck: {}, longest_it_streak: {}, mean_turns_to_tag: {}, tags_given: {}, \
+                 tags_received: {}, mean_distance_from_it: {}, mean_turn_position: {}",
+                stats_for_player.distance_moved,
+                stats_for_player.turns_stuck,
+                stats_for_player.longest_it_streak,
+                format_optional(stats_for_player.mean_turns_to_tag()),
+                stats_for_player.tags_given,
+                stats_for_player.tags_received,
+                format_optional(stats_for_player.mean_distance_from_it()),
+                format_optional(stats_for_player.mean_turn_position())
+            )?;
+            if self.move_resolution == MoveResolution::Simultaneous {
+                write!(f, ", conflicts_lost: {}", stats_for_player.conflicts_lost)?;
+            }
+            if self.can_sprint() {
+                write!(
+                    f,
+                    ", max_speed: {}, max_stamina: {}, stamina_spent: {}, stamina_recovered: {}",
+                    stats_for_player.max_speed,
+                    stats_for_player.max_stamina,
+                    stats_for_player.stamina_spent,
+                    stats_for_player.stamina_recovered
+                )?;
+            }
+            if self.vision.is_some() {
+                write!(f, ", it_sightings: {}", stats_for_player.it_sightings)?;
+            }
+            writeln!(f)?;
+        }
+        let correlations: Vec<String> = self
+            .risk_tolerance_correlations()
+            .into_iter()
+            .map(|(name, correlation)| format!("{}: {}", name, format_optional(correlation)))
+            .collect();
+        writeln!(f, "correlation with risk_tolerance: {}", correlations.join(", "))?;
+
+        Ok(())
     }
 }
+
+#[cfg(test)]
+use super::field_of_play::FieldOfPlay;
+#[cfg(test)]
+use super::rng::new_rng;
+
+#[test]
+fn stats_tag_frequency_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut rng = new_rng(0);
+    let players: Vec<Player> = (0..3)
+        .map(|index| Player::new(index, index == 0, &mut field_of_play, &mut rng))
+        .collect();
+    let mut stats = Stats::new(&players, 100, 3, 3);
+    assert_eq!(stats.mean_turns_between_tags(), None);
+
+    stats.record_new_it_details("p1".to_owned(), 10);
+    stats.record_new_it_details("p2".to_owned(), 30);
+    stats.record_turn_played(100);
+
+    assert_eq!(stats.tag_count(), 2);
+    assert_eq!(stats.tags_per_turn(), 0.02);
+    assert_eq!(stats.mean_turns_between_tags(), Some(15.0));
+}
+
+#[test]
+fn stats_freeze_tag_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut rng = new_rng(0);
+    let players: Vec<Player> = (0..3)
+        .map(|index| Player::new(index, index == 0, &mut field_of_play, &mut rng))
+        .collect();
+    let mut stats = Stats::new(&players, 100, 3, 3);
+    stats.set_game_mode(GameMode::Freeze);
+
+    stats.record_frozen_details("p1", 5);
+    stats.record_rescue_details("p2");
+    stats.record_frozen_details("p1", 8);
+    stats.record_frozen_details("p2", 9);
+    stats.record_all_frozen(9);
+
+    assert_eq!(stats.freeze_count(), 3);
+    assert_eq!(stats.rescue_count(), 1);
+    assert_eq!(stats.tag_count(), 3);
+    assert_eq!(stats.all_frozen_turn(), Some(9));
+    assert!(stats
+        .to_string()
+        .contains("freezes: 3, rescues: 1, all frozen on turn: 9"));
+}
+
+#[test]
+fn stats_infection_curve_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut rng = new_rng(0);
+    let players: Vec<Player> = (0..3)
+        .map(|index| Player::new(index, index == 0, &mut field_of_play, &mut rng))
+        .collect();
+    let mut stats = Stats::new(&players, 100, 3, 3);
+    stats.set_game_mode(GameMode::Infection);
+
+    stats.record_it_player_count(1);
+    stats.record_new_it_details("p1".to_owned(), 2);
+    stats.record_it_player_count(2);
+    stats.record_new_it_details("p2".to_owned(), 3);
+    stats.record_it_player_count(3);
+    stats.record_all_infected(3);
+
+    assert_eq!(stats.it_players_per_turn(), &[1, 2, 3]);
+    assert_eq!(stats.all_infected_turn(), Some(3));
+    assert!(stats.to_string().contains("it players per turn: 1, 2, 3"));
+}
+
+#[test]
+fn stats_player_metrics_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut rng = new_rng(0);
+    let players: Vec<Player> = (0..3)
+        .map(|index| Player::new(index, index == 0, &mut field_of_play, &mut rng))
+        .collect();
+    let mut stats = Stats::new(&players, 100, 3, 3);
+
+    stats.record_start_player_details(&players[0], 0);
+    let moved = MoveRecord {
+        distance: 1,
+        ..MoveRecord::default()
+    };
+    stats.record_move("p0", moved);
+    stats.record_every_player_start(&players, &[0, 2, 1]);
+    stats.record_every_player_move(&[Some(MoveRecord::default()), None, Some(moved)]);
+    stats.record_tag_given("p0", "p1", 4);
+    stats.record_new_it_details("p1".to_owned(), 4);
+    stats.record_tag_given("p1", "p0", 7);
+    stats.record_new_it_details("p0".to_owned(), 7);
+    stats.record_distances_from_it(&[None, Some(3), Some(1)]);
+    stats.record_distances_from_it(&[None, None, Some(2)]);
+
+    let player_stats = stats.player_stats();
+    assert_eq!(player_stats["p0"].distance_moved, 1);
+    assert_eq!(player_stats["p0"].turns_stuck, 1);
+    assert_eq!(player_stats["p2"].distance_moved, 1);
+    assert_eq!(player_stats["p1"].mean_turn_position(), Some(2.0));
+    assert_eq!(player_stats["p0"].longest_it_streak, 2);
+    assert_eq!(player_stats["p0"].tags_given, 1);
+    assert_eq!(player_stats["p0"].tags_received, 1);
+    assert_eq!(player_stats["p0"].mean_turns_to_tag(), Some(4.0));
+    assert_eq!(player_stats["p1"].mean_turns_to_tag(), Some(3.0));
+    assert_eq!(player_stats["p2"].mean_turns_to_tag(), None);
+    assert_eq!(player_stats["p0"].it_since_turn, Some(7));
+    assert_eq!(player_stats["p1"].it_since_turn, None);
+    assert_eq!(player_stats["p2"].mean_distance_from_it(), Some(1.5));
+    assert_eq!(player_stats["p1"].mean_distance_from_it(), Some(3.0));
+    assert_eq!(player_stats["p0"].mean_distance_from_it(), None);
+    assert_eq!(stats.tag_network().weight(0, 1), 1);
+    assert_eq!(stats.tag_network().reciprocity(), Some(1.0));
+}
+
+#[test]
+fn pearson_correlation_test() {
+    assert_eq!(pearson_correlation(&[(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]), Some(1.0));
+    assert_eq!(pearson_correlation(&[(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)]), Some(-1.0));
+    assert_eq!(pearson_correlation(&[(1.0, 2.0), (2.0, 2.0)]), None);
+    assert_eq!(pearson_correlation(&[(1.0, 2.0)]), None);
+    // Two points that vary would always give 1 or -1.
+    assert_eq!(pearson_correlation(&[(1.0, 2.0), (2.0, 5.0)]), None);
+    assert_eq!(pearson_correlation(&[(1.0, 5.0), (2.0, 2.0)]), None);
+}
@@ -0,0 +1,227 @@
+use super::action::Action;
+use super::direction::Direction;
+use super::field_of_play::FieldOfPlay;
+use super::player::Player;
+use super::rng::SimRng;
+use log::error;
+use rand::Rng;
+
+/// The chance that a player with vision tries to keep going the way they face before picking a
+/// random direction.
+pub const KEEP_GOING_CHANCE: f64 = 0.5;
+
+/// Decides how a player wants to move on their turn. A strategy only gets a read-only view of the
+/// field of play and the acting player, so it can not break the rules of the game directly. The
+/// [Player] validates the returned actions and ignores any that are not legal, such as moving into
+/// an occupied position.
+pub trait MovementStrategy: Send + Sync {
+    /// Returns the move actions the player would like to take this turn. Only the first legal move is
+    /// used, and returning no moves means the player stays where they are. A player who can move more
+    /// than one position this turn is asked again from wherever each move leaves them, so strategies
+    /// can return single steps, but may also return a whole path with [Action::new_path].
+    fn choose_actions(&self, field_of_play: &FieldOfPlay, player: &Player, rng: &mut SimRng) -> Vec<Action>;
+
+    /// Called once at the start of every turn, before anyone chooses their actions. Strategies that
+    /// need to know about every player can look at the whole field here, rather than on every
+    /// decision. Players may have moved since by the time a decision is made.
+    fn start_turn(&mut self, _field_of_play: &FieldOfPlay) {}
+
+    /// A short human readable name used in logs and stats.
+    fn name(&self) -> &str;
+}
+
+/// The strategies used during a game. Players who are it use the chaser strategy and everyone else
+/// uses the runner strategy.
+pub struct Strategies {
+    pub runner: Box<dyn MovementStrategy>,
+    pub chaser: Box<dyn MovementStrategy>
+}
+
+impl Strategies {
+    pub fn for_player(&self, player: &Player) -> &dyn MovementStrategy {
+        if player.is_it {
+            self.chaser.as_ref()
+        } else {
+            self.runner.as_ref()
+        }
+    }
+
+    pub fn start_turn(&mut self, field_of_play: &FieldOfPlay) {
+        self.runner.start_turn(field_of_play);
+        self.chaser.start_turn(field_of_play);
+    }
+}
+
+impl Default for Strategies {
+    fn default() -> Self {
+        Strategies {
+            runner: Box::new(RandomAvoidanceStrategy),
+            chaser: Box::new(RandomAvoidanceStrategy)
+        }
+    }
+}
+
+/// The default behaviour. The player picks a random direction and tries to avoid moving closer to
+/// where they believe the it player is, unless the move is within their risk tolerance. Players with
+/// vision are more likely to keep going the way they face.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomAvoidanceStrategy;
+
+impl MovementStrategy for RandomAvoidanceStrategy {
+    fn choose_actions(&self, field_of_play: &FieldOfPlay, player: &Player, rng: &mut SimRng) -> Vec<Action> {
+        let (x, y) = self.move_to_empty_position(field_of_play, player, rng);
+
+        vec![Action::new_move(x, y)]
+    }
+
+    fn name(&self) -> &str {
+        "random-avoidance"
+    }
+}
+
+impl RandomAvoidanceStrategy {
+    fn move_to_empty_position(&self, field_of_play: &FieldOfPlay, player: &Player, rng: &mut SimRng) -> (usize, usize) {
+        let (current_x, current_y) = player.get_location();
+        let mut found_location = false;
+        let mut retries_remaining = 1000;
+        let mut x_coordinate = current_x;
+        let mut y_coordinate = current_y;
+        // Only players with vision roll for this, so games without vision play the same as before
+        // players faced a direction.
+        let mut keep_going = player.get_vision().is_some() && rng.gen_bool(KEEP_GOING_CHANCE);
+
+        while !found_location {
+            let rand_direction = if std::mem::take(&mut keep_going) {
+                player.get_facing()
+            } else {
+                match rng.gen_range(0..8) {
+                    0 => Direction::West,
+                    1 => Direction::East,
+                    2 => Direction::North,
+                    3 => Direction::South,
+                    4 => Direction::NorthWest,
+                    5 => Direction::NorthEast,
+                    6 => Direction::SouthWest,
+                    7 => Direction::SouthEast,
+                    _ => unreachable!()
+                }
+            };
+            if field_of_play.is_position_valid_and_empty(rand_direction, current_x, current_y) {
+                // The position is valid so it is on the field, wrapping around the edges if the
+                // topology allows it.
+                (x_coordinate, y_coordinate) = field_of_play
+                    .get_position_in_direction(rand_direction, current_x, current_y)
+                    .expect("A valid position is always on the field.");
+                found_location = true;
+            }
+
+            // It is better to move than get stuck because we do not want to get closer to the it
+            // player. So we only try to find a new position that isn't closer to the it player if
+            // we have at least 100 retries remaining.
+            if is_new_coordinates_too_close_to_it_player(player, x_coordinate, y_coordinate, field_of_play)
+                && retries_remaining > 100
+            {
+                found_location = false;
+            }
+
+            // It is possible that players will get bunched up at the edge of the field and the
+            // current player has nowhere to go. If this is the case then skip this player and let
+            // the next one try to move.
+            retries_remaining += -1;
+            if retries_remaining <= 0 {
+                error!("Player: {} is stuck", player.name);
+                found_location = true;
+            }
+        }
+
+        (x_coordinate, y_coordinate)
+    }
+}
+
+// distance between two sets of points is d=sqrt(x2-x1)^2 + (y2-y1)^2. If the x and y input are
+// closer to the it player than the player's current coordinates we return false. Using floats
+// for calc instead of and arb precision type, like BigDecimal means we may have slightly less
+// reliable results, but an argument can be made that this makes the agents more human-like.
+fn is_new_coordinates_too_close_to_it_player(player: &Player, x: usize, y: usize, field_of_play: &FieldOfPlay) -> bool {
+    let (current_x, current_y) = player.get_location();
+    let it_coordinates = player.get_last_known_it_location();
+    if let Some((it_x, it_y)) = it_coordinates {
+        let (new_x_distance, new_y_distance) = field_of_play.get_axis_distances((it_x, it_y), (x, y));
+        let (current_x_distance, current_y_distance) =
+            field_of_play.get_axis_distances((it_x, it_y), (current_x, current_y));
+        let new_distance = ((new_x_distance.pow(2) + new_y_distance.pow(2)) as f64).sqrt();
+        let current_distance = ((current_x_distance.pow(2) + current_y_distance.pow(2)) as f64).sqrt();
+        if new_distance < current_distance {
+            return is_new_distance_outside_risk_tolerance(player, new_distance, current_distance);
+        }
+    }
+
+    false
+}
+
+// players with greater risk tolerance take more risks. They're willing to move closer to the
+// it player.
+fn is_new_distance_outside_risk_tolerance(player: &Player, new_distance: f64, current_distance: f64) -> bool {
+    let pct_chg = ((new_distance - current_distance).abs() / current_distance) * 100.0;
+    pct_chg > player.get_risk_tolerance()
+}
+
+#[cfg(test)]
+use super::rng::new_rng;
+
+#[test]
+fn random_avoidance_moves_to_only_empty_position_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    (0..3).for_each(|y| (0..3).for_each(|x| field_of_play.set_occupant(x, y, Some(100))));
+    field_of_play.set_occupant(1, 1, None);
+    let mut rng = new_rng(0);
+    let player = Player::new(1, false, &mut field_of_play, &mut rng);
+    assert_eq!(player.get_location(), (1, 1));
+    field_of_play.set_occupant(1, 2, None);
+
+    let actions = RandomAvoidanceStrategy.choose_actions(&field_of_play, &player, &mut rng);
+
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].x_coordinate, Some(1));
+    assert_eq!(actions[0].y_coordinate, Some(2));
+}
+
+#[test]
+// Normally, you would not test private functions like this. But, for the purposes of this exercise
+// we will for now due to time constraints
+fn is_new_coordinates_too_close_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 3);
+    let mut rng = new_rng(0);
+    let mut player = Player::new(1, false, &mut field_of_play, &mut rng);
+    let mut it_player = Player::new(2, true, &mut field_of_play, &mut rng);
+    player.set_risk_tolerance(10.0);
+    it_player.set_location(2, 2);
+    let (it_x, it_y) = it_player.get_location();
+    field_of_play.set_last_known_it_location(it_x, it_y);
+    player.look_for_it(&field_of_play, &[]);
+
+    // test moving away from it
+    player.set_location(2, 1);
+
+    assert_eq!(player.get_last_known_it_location(), Some((it_x, it_y)));
+    assert!(!is_new_coordinates_too_close_to_it_player(
+        &player,
+        0,
+        0,
+        &field_of_play
+    ));
+
+    // test moving closer but within tolerance
+    player.set_location(0, 0);
+    assert!(is_new_coordinates_too_close_to_it_player(&player, 0, 1, &field_of_play));
+
+    // test moving closer but outside tolerance
+    player.set_location(0, 0);
+    player.set_risk_tolerance(25.0);
+    assert!(!is_new_coordinates_too_close_to_it_player(
+        &player,
+        0,
+        1,
+        &field_of_play
+    ));
+}
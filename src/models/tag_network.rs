@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Who tagged whom over a game, as a directed graph with a node for every player and an edge from
+/// each tagger to each player they tagged, weighted by how many times they tagged them. Players are
+/// identified by their index.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TagNetwork {
+    num_players: usize,
+    // A BTreeMap keeps edges in order so the exported graphs are the same for the same seed.
+    edges: BTreeMap<(usize, usize), usize>
+}
+
+impl TagNetwork {
+    pub fn new(num_players: usize) -> Self {
+        TagNetwork {
+            num_players,
+            edges: BTreeMap::new()
+        }
+    }
+
+    pub fn record_tag(&mut self, tagger: usize, tagged: usize) {
+        *self.edges.entry((tagger, tagged)).or_insert(0) += 1;
+    }
+
+    /// How many times `tagger` tagged `tagged`.
+    pub fn weight(&self, tagger: usize, tagged: usize) -> usize {
+        self.edges.get(&(tagger, tagged)).copied().unwrap_or(0)
+    }
+
+    /// Every edge as (tagger, tagged, weight), ordered by tagger then tagged.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.edges
+            .iter()
+            .map(|(&(tagger, tagged), &weight)| (tagger, tagged, weight))
+    }
+
+    /// For every player, by index, the number of different players they tagged and the number of
+    /// different players who tagged them.
+    pub fn degrees(&self) -> Vec<(usize, usize)> {
+        let mut degrees = vec![(0, 0); self.num_players];
+        for (tagger, tagged) in self.edges.keys() {
+            degrees[*tagger].0 += 1;
+            degrees[*tagged].1 += 1;
+        }
+
+        degrees
+    }
+
+    /// The share of edges whose reverse is also an edge, meaning the two players tagged each other at
+    /// some point. None if nobody was tagged.
+    pub fn reciprocity(&self) -> Option<f64> {
+        if self.edges.is_empty() {
+            return None;
+        }
+        let reciprocated = self
+            .edges
+            .keys()
+            .filter(|(tagger, tagged)| self.edges.contains_key(&(*tagged, *tagger)))
+            .count();
+
+        Some(reciprocated as f64 / self.edges.len() as f64)
+    }
+
+    /// The graph in the Graphviz DOT language. Edges are labelled with their weight.
+    pub fn to_dot(&self) -> String {
+        // Writing to a String can not fail.
+        let mut dot = String::from("digraph tags {\n");
+        for player in 0..self.num_players {
+            let _ = writeln!(dot, "    p{};", player);
+        }
+        for (tagger, tagged, weight) in self.edges() {
+            let _ = writeln!(
+                dot,
+                "    p{} -> p{} [weight={}, label=\"{}\"];",
+                tagger, tagged, weight, weight
+            );
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// The graph as GraphML, with the weight of every edge in its `weight` data.
+    pub fn to_graphml(&self) -> String {
+        let mut graphml = String::new();
+        let _ = writeln!(graphml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(graphml, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#);
+        let _ = writeln!(
+            graphml,
+            r#"  <key id="weight" for="edge" attr.name="weight" attr.type="int"/>"#
+        );
+        let _ = writeln!(graphml, r#"  <graph id="tags" edgedefault="directed">"#);
+        for player in 0..self.num_players {
+            let _ = writeln!(graphml, r#"    <node id="p{}"/>"#, player);
+        }
+        for (tagger, tagged, weight) in self.edges() {
+            let _ = writeln!(
+                graphml,
+                r#"    <edge source="p{}" target="p{}"><data key="weight">{}</data></edge>"#,
+                tagger, tagged, weight
+            );
+        }
+        graphml.push_str("  </graph>\n</graphml>\n");
+
+        graphml
+    }
+}
+
+#[test]
+fn tag_network_test() {
+    let mut tag_network = TagNetwork::new(3);
+    assert_eq!(tag_network.reciprocity(), None);
+
+    tag_network.record_tag(0, 1);
+    tag_network.record_tag(1, 2);
+    tag_network.record_tag(2, 1);
+    tag_network.record_tag(0, 1);
+
+    assert_eq!(tag_network.weight(0, 1), 2);
+    assert_eq!(tag_network.weight(1, 0), 0);
+    assert_eq!(tag_network.degrees(), vec![(1, 0), (1, 2), (1, 1)]);
+    assert_eq!(tag_network.reciprocity(), Some(2.0 / 3.0));
+    assert_eq!(
+        tag_network.to_dot(),
+        "digraph tags {\n    p0;\n    p1;\n    p2;\n    p0 -> p1 [weight=2, label=\"2\"];\n    p1 -> p2 [weight=1, \
+         label=\"1\"];\n    p2 -> p1 [weight=1, label=\"1\"];\n}\n"
+    );
+    let graphml = tag_network.to_graphml();
+    assert_eq!(graphml.matches("<node ").count(), 3);
+    assert!(graphml.contains(r#"<edge source="p0" target="p1"><data key="weight">2</data></edge>"#));
+}
@@ -0,0 +1,53 @@
+/// What a position on the field is made of. Players can only stand on open ground.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Terrain {
+    #[default]
+    Open,
+    Wall,
+    Tree,
+    Water
+}
+
+impl Terrain {
+    pub const OBSTACLES: [Terrain; 3] = [Terrain::Wall, Terrain::Tree, Terrain::Water];
+
+    pub fn is_passable(self) -> bool {
+        self == Terrain::Open
+    }
+
+    /// Whether players can see past this terrain. Water is low enough to see across.
+    pub fn blocks_sight(self) -> bool {
+        self == Terrain::Wall || self == Terrain::Tree
+    }
+
+    /// The character used for this terrain in rendered fields and map files.
+    pub fn symbol(self) -> char {
+        match self {
+            Terrain::Open => '.',
+            Terrain::Wall => '#',
+            Terrain::Tree => 'T',
+            Terrain::Water => '~'
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Terrain> {
+        match symbol {
+            '.' => Some(Terrain::Open),
+            '#' => Some(Terrain::Wall),
+            'T' => Some(Terrain::Tree),
+            '~' => Some(Terrain::Water),
+            _ => None
+        }
+    }
+}
+
+#[test]
+fn terrain_symbol_round_trip_test() {
+    [Terrain::Open, Terrain::Wall, Terrain::Tree, Terrain::Water]
+        .iter()
+        .for_each(|terrain| assert_eq!(Terrain::from_symbol(terrain.symbol()), Some(*terrain)));
+    assert_eq!(Terrain::from_symbol('?'), None);
+    assert!(Terrain::Open.is_passable());
+    assert!(Terrain::OBSTACLES.iter().all(|terrain| !terrain.is_passable()));
+    assert!(!Terrain::Water.blocks_sight() && Terrain::Tree.blocks_sight());
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the edges of the field of play behave.
+/// - Bounded: the edges are walls.
+/// - Torus: walking off any edge brings a player back on at the opposite edge.
+/// - WrapX: only the left and right edges are joined, the top and bottom are walls.
+/// - WrapY: only the top and bottom edges are joined, the left and right are walls.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Topology {
+    #[default]
+    Bounded,
+    Torus,
+    WrapX,
+    WrapY
+}
+
+impl Topology {
+    pub const NAMES: [&'static str; 4] = ["bounded", "torus", "wrap-x", "wrap-y"];
+
+    pub fn wraps_x(self) -> bool {
+        self == Topology::Torus || self == Topology::WrapX
+    }
+
+    pub fn wraps_y(self) -> bool {
+        self == Topology::Torus || self == Topology::WrapY
+    }
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "bounded" => Ok(Topology::Bounded),
+            "torus" => Ok(Topology::Torus),
+            "wrap-x" => Ok(Topology::WrapX),
+            "wrap-y" => Ok(Topology::WrapY),
+            _ => Err(format!(
+                "unknown topology: {}. Must be one of: {}",
+                name,
+                Topology::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Topology::Bounded => "bounded",
+            Topology::Torus => "torus",
+            Topology::WrapX => "wrap-x",
+            Topology::WrapY => "wrap-y"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Moves `coordinate` by `offset` along an axis `len` positions long. Returns None if that leaves
+/// the axis and it does not wrap.
+pub fn offset_coordinate(coordinate: usize, offset: isize, len: usize, wraps: bool) -> Option<usize> {
+    if wraps {
+        let len = len as isize;
+        return Some((coordinate as isize + offset).rem_euclid(len) as usize);
+    }
+
+    coordinate
+        .checked_add_signed(offset)
+        .filter(|new_coordinate| *new_coordinate < len)
+}
+
+/// The number of steps between two coordinates on an axis `len` positions long, going around the
+/// edge if the axis wraps and that is shorter.
+pub fn axis_distance(from: usize, to: usize, len: usize, wraps: bool) -> usize {
+    let distance = from.abs_diff(to);
+    if wraps {
+        return distance.min(len - distance);
+    }
+
+    distance
+}
+
+/// The number of steps from one coordinate to another on an axis `len` positions long, negative when
+/// going towards 0. Goes around the edge if the axis wraps and that is shorter.
+pub fn axis_offset(from: usize, to: usize, len: usize, wraps: bool) -> isize {
+    let offset = to as isize - from as isize;
+    let len = len as isize;
+    if wraps && offset.abs() * 2 > len {
+        return offset - offset.signum() * len;
+    }
+
+    offset
+}
+
+#[test]
+fn topology_from_str_test() {
+    assert_eq!("torus".parse::<Topology>(), Ok(Topology::Torus));
+    assert!("klein-bottle".parse::<Topology>().is_err());
+    Topology::NAMES.iter().for_each(|name| {
+        assert_eq!(name.parse::<Topology>().unwrap().to_string(), *name);
+    });
+    assert!(Topology::WrapX.wraps_x() && !Topology::WrapX.wraps_y());
+    assert!(!Topology::Bounded.wraps_x() && !Topology::Bounded.wraps_y());
+}
+
+#[test]
+fn topology_wrapping_test() {
+    assert_eq!(offset_coordinate(0, -1, 5, false), None);
+    assert_eq!(offset_coordinate(4, 1, 5, false), None);
+    assert_eq!(offset_coordinate(0, -1, 5, true), Some(4));
+    assert_eq!(offset_coordinate(4, 1, 5, true), Some(0));
+    assert_eq!(offset_coordinate(2, 7, 5, true), Some(4));
+
+    assert_eq!(axis_distance(0, 4, 5, false), 4);
+    assert_eq!(axis_distance(0, 4, 5, true), 1);
+    assert_eq!(axis_distance(1, 3, 5, true), 2);
+
+    assert_eq!(axis_offset(0, 4, 5, false), 4);
+    assert_eq!(axis_offset(0, 4, 5, true), -1);
+    assert_eq!(axis_offset(4, 0, 5, true), 1);
+    assert_eq!(axis_offset(3, 1, 5, true), -2);
+}
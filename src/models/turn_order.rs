@@ -0,0 +1,124 @@
+use super::player::Player;
+use super::rng::SimRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The order players act in during a turn.
+/// - Fixed: by index, so p0 always acts first.
+/// - Shuffled: a new random order every turn.
+/// - ItFirst: it players act before everyone else, each group in index order.
+/// - ItLast: it players act after everyone else, each group in index order.
+/// - Initiative: every player rolls initiative once at the start of the game and the highest roll acts first for the
+///   whole game.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TurnOrder {
+    #[default]
+    Fixed,
+    Shuffled,
+    ItFirst,
+    ItLast,
+    Initiative
+}
+
+impl TurnOrder {
+    pub const NAMES: [&'static str; 5] = ["fixed", "shuffled", "it-first", "it-last", "initiative"];
+
+    /// The order players act in for the whole game when rolling initiative, or an empty order for
+    /// every other turn order. Only initiative uses `rng`, so the other orders play the same game
+    /// for the same seed as before turn orders could be chosen.
+    pub fn roll_initiative(self, num_players: usize, rng: &mut SimRng) -> Vec<usize> {
+        if self != TurnOrder::Initiative {
+            return Vec::new();
+        }
+        let rolls: Vec<u32> = (0..num_players).map(|_| rng.gen()).collect();
+        let mut order: Vec<usize> = (0..num_players).collect();
+        // Ties go to the lower index.
+        order.sort_by_key(|index| std::cmp::Reverse(rolls[*index]));
+
+        order
+    }
+
+    /// The indices of `players` in the order they act this turn. `initiative` is the order from
+    /// [TurnOrder::roll_initiative].
+    pub fn order(self, players: &[Player], initiative: &[usize], rng: &mut SimRng) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..players.len()).collect();
+        match self {
+            TurnOrder::Fixed => {}
+            TurnOrder::Shuffled => order.shuffle(rng),
+            // Sorting is stable, so each group keeps index order.
+            TurnOrder::ItFirst => order.sort_by_key(|index| !players[*index].is_it),
+            TurnOrder::ItLast => order.sort_by_key(|index| players[*index].is_it),
+            TurnOrder::Initiative => order = initiative.to_vec()
+        }
+
+        order
+    }
+}
+
+impl FromStr for TurnOrder {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "fixed" => Ok(TurnOrder::Fixed),
+            "shuffled" => Ok(TurnOrder::Shuffled),
+            "it-first" => Ok(TurnOrder::ItFirst),
+            "it-last" => Ok(TurnOrder::ItLast),
+            "initiative" => Ok(TurnOrder::Initiative),
+            _ => Err(format!(
+                "unknown turn order: {}. Must be one of: {}",
+                name,
+                TurnOrder::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for TurnOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TurnOrder::Fixed => "fixed",
+            TurnOrder::Shuffled => "shuffled",
+            TurnOrder::ItFirst => "it-first",
+            TurnOrder::ItLast => "it-last",
+            TurnOrder::Initiative => "initiative"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+use super::field_of_play::FieldOfPlay;
+#[cfg(test)]
+use super::rng::new_rng;
+
+#[test]
+fn turn_order_test() {
+    TurnOrder::NAMES.iter().for_each(|name| {
+        assert_eq!(name.parse::<TurnOrder>().unwrap().to_string(), *name);
+    });
+    assert!("alphabetical".parse::<TurnOrder>().is_err());
+
+    let mut field_of_play = FieldOfPlay::new(4, 4);
+    let mut rng = new_rng(5);
+    let players: Vec<Player> = (0..4)
+        .map(|index| Player::new(index, index == 2, &mut field_of_play, &mut rng))
+        .collect();
+
+    assert_eq!(TurnOrder::Fixed.order(&players, &[], &mut rng), vec![0, 1, 2, 3]);
+    assert_eq!(TurnOrder::ItFirst.order(&players, &[], &mut rng), vec![2, 0, 1, 3]);
+    assert_eq!(TurnOrder::ItLast.order(&players, &[], &mut rng), vec![0, 1, 3, 2]);
+    let mut shuffled = TurnOrder::Shuffled.order(&players, &[], &mut rng);
+    shuffled.sort_unstable();
+    assert_eq!(shuffled, vec![0, 1, 2, 3]);
+
+    assert!(TurnOrder::Fixed.roll_initiative(4, &mut rng).is_empty());
+    let initiative = TurnOrder::Initiative.roll_initiative(4, &mut rng);
+    assert_eq!(initiative.len(), 4);
+    assert_eq!(TurnOrder::Initiative.order(&players, &initiative, &mut rng), initiative);
+}
@@ -0,0 +1,121 @@
+use super::direction::Direction;
+use super::field_of_play::FieldOfPlay;
+
+/// How far and how wide players can see. A player sees positions up to `range` positions away in a
+/// straight line, inside a cone `angle` degrees wide centred on the direction they face. Other
+/// players and obstacles that block sight hide anything behind them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Vision {
+    pub range: usize,
+    /// Between 1 and 360 degrees. 360 lets players see all the way around themselves.
+    pub angle: u32
+}
+
+impl Vision {
+    pub const DEFAULT_ANGLE: u32 = 120;
+
+    /// Returns an error if players could not see anything, or the angle is more than a full circle.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.range == 0 {
+            return Err("the vision range must be at least 1".to_owned());
+        }
+        if self.angle == 0 || self.angle > 360 {
+            return Err("the vision angle must be between 1 and 360 degrees".to_owned());
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if a player at `from`, facing `facing`, can see the position `to`.
+    pub fn can_see(
+        &self,
+        field_of_play: &FieldOfPlay,
+        from: (usize, usize),
+        facing: Direction,
+        to: (usize, usize)
+    ) -> bool {
+        let offset = field_of_play.get_offset(from, to);
+        if offset == (0, 0) {
+            return true;
+        }
+        let distance = length(offset);
+        if distance > self.range as f64 {
+            return false;
+        }
+        let facing_offset = facing.offset();
+        let cos = (facing_offset.0 * offset.0 + facing_offset.1 * offset.1) as f64 / (length(facing_offset) * distance);
+        // Allow for rounding, so positions on the edge of the cone can be seen.
+        let half_angle = (self.angle as f64 / 2.0).to_radians() + 1e-9;
+        if cos.clamp(-1.0, 1.0).acos() > half_angle {
+            return false;
+        }
+
+        is_line_of_sight_clear(field_of_play, from, offset)
+    }
+}
+
+fn length(offset: (isize, isize)) -> f64 {
+    ((offset.0 * offset.0 + offset.1 * offset.1) as f64).sqrt()
+}
+
+// Walks the straight line from `from` to the position `offset` away and returns false if anything
+// between them blocks the view. The ends of the line never block it.
+fn is_line_of_sight_clear(field_of_play: &FieldOfPlay, from: (usize, usize), offset: (isize, isize)) -> bool {
+    let steps = offset.0.abs().max(offset.1.abs());
+    (1..steps).all(|step| {
+        let fraction = step as f64 / steps as f64;
+        let partial_offset = (
+            (offset.0 as f64 * fraction).round() as isize,
+            (offset.1 as f64 * fraction).round() as isize
+        );
+        match field_of_play.get_position_at_offset(from.0, from.1, partial_offset) {
+            Some((x, y)) => {
+                field_of_play.get_occupant(x, y).is_none() && !field_of_play.get_terrain(x, y).blocks_sight()
+            }
+            None => false
+        }
+    })
+}
+
+#[cfg(test)]
+use super::terrain::Terrain;
+#[cfg(test)]
+use super::topology::Topology;
+
+#[test]
+fn vision_validate_test() {
+    assert!(Vision { range: 3, angle: 360 }.validate().is_ok());
+    assert!(Vision { range: 0, angle: 90 }.validate().is_err());
+    assert!(Vision { range: 3, angle: 0 }.validate().is_err());
+    assert!(Vision { range: 3, angle: 361 }.validate().is_err());
+}
+
+#[test]
+fn vision_can_see_test() {
+    let mut field_of_play = FieldOfPlay::new(9, 9);
+    let vision = Vision { range: 4, angle: 90 };
+    let from = (4, 4);
+
+    // In range and inside the cone, including its edges.
+    assert!(vision.can_see(&field_of_play, from, Direction::North, (4, 0)));
+    assert!(vision.can_see(&field_of_play, from, Direction::North, (2, 2)));
+    assert!(vision.can_see(&field_of_play, from, Direction::NorthEast, (8, 4)));
+    // Out of range, or outside the cone.
+    assert!(!vision.can_see(&field_of_play, from, Direction::North, (1, 1)));
+    assert!(!vision.can_see(&field_of_play, from, Direction::North, (4, 5)));
+    assert!(!vision.can_see(&field_of_play, from, Direction::North, (7, 3)));
+    assert!(Vision { range: 4, angle: 360 }.can_see(&field_of_play, from, Direction::North, (4, 5)));
+
+    // Players and obstacles that block sight hide what is behind them, but water does not.
+    field_of_play.set_occupant(4, 2, Some(1));
+    assert!(vision.can_see(&field_of_play, from, Direction::North, (4, 2)));
+    assert!(!vision.can_see(&field_of_play, from, Direction::North, (4, 1)));
+    field_of_play.set_terrain(3, 3, Terrain::Water);
+    assert!(vision.can_see(&field_of_play, from, Direction::North, (2, 2)));
+    field_of_play.set_terrain(3, 3, Terrain::Wall);
+    assert!(!vision.can_see(&field_of_play, from, Direction::North, (2, 2)));
+
+    // Players see around the edge of a wrapping field.
+    field_of_play.set_topology(Topology::Torus);
+    assert!(vision.can_see(&field_of_play, (4, 1), Direction::North, (4, 7)));
+}
@@ -1,26 +1,124 @@
+use crate::image_renderer::{Palette, Rgb};
+use crate::models::field_of_play::FieldOfPlay;
+use crate::models::heatmap::{Heatmap, HeatmapKind};
 use crate::models::player::Player;
-use crate::FieldOfPlay;
 
-// This is very rudimentary but gets the job done for now
-pub fn render_field(field_of_play: &FieldOfPlay, players: &[Player], turn_num: usize) {
-    println!("/// TURN {}", turn_num);
-    field_of_play.field.iter().for_each(|y_axis| {
+// This is very rudimentary but gets the job done for now. The caller is responsible for printing the
+// returned string.
+pub fn render_field(field_of_play: &FieldOfPlay, players: &[Player], turn_num: usize) -> String {
+    let mut rendered = format!("/// TURN {}\n", turn_num);
+    // Edges that wrap around are marked so it is clear players can walk off them. Rows are wrapped
+    // in < and > when the x-axis wraps and the field is topped with ^ and tailed with v when the
+    // y-axis wraps.
+    let topology = field_of_play.topology();
+    let (row_start, row_end) = if topology.wraps_x() { ("<", ">") } else { ("", "") };
+    let wrap_row = |marker: &str| {
+        format!(
+            "{}{}{}\n",
+            " ".repeat(row_start.len()),
+            marker.repeat(field_of_play.x_len()),
+            " ".repeat(row_end.len())
+        )
+    };
+    if topology.wraps_y() {
+        rendered.push_str(&wrap_row("^"));
+    }
+    (0..field_of_play.y_len()).for_each(|y| {
         let mut y_axis_as_string = String::new();
-        y_axis.iter().for_each(|x_axis_element| {
-            let x_axis_element = match x_axis_element {
+        (0..field_of_play.x_len()).for_each(|x| {
+            let x_axis_element = match field_of_play.get_occupant(x, y) {
                 Some(player_index) => {
-                    if players[*player_index].is_it {
+                    if players[player_index].is_it {
                         "*"
+                    } else if players[player_index].is_frozen {
+                        "F"
                     } else {
                         "P"
                     }
                 }
                 None => "-"
             };
+            let terrain = field_of_play.get_terrain(x, y);
 
-            let x_element_as_str = x_axis_element.to_owned();
+            let x_element_as_str = if terrain.is_passable() {
+                x_axis_element.to_owned()
+            } else {
+                terrain.symbol().to_string()
+            };
             y_axis_as_string = format!("{}{}", y_axis_as_string, x_element_as_str);
         });
-        println!("{}", y_axis_as_string);
+        rendered.push_str(row_start);
+        rendered.push_str(&y_axis_as_string);
+        rendered.push_str(row_end);
+        rendered.push('\n');
     });
+    if topology.wraps_y() {
+        rendered.push_str(&wrap_row("v"));
+    }
+
+    rendered
+}
+
+/// Draws a heatmap with every position as two spaces on a coloured background, using the same colours
+/// as [ImageRenderer::write_heatmap_png](crate::image_renderer::ImageRenderer::write_heatmap_png).
+/// Obstacles are drawn with their map symbol. The terminal must support 24-bit colour.
+pub fn render_heatmap(heatmap: &Heatmap, kind: HeatmapKind, field_of_play: &FieldOfPlay) -> String {
+    let palette = Palette::default();
+    let max = heatmap.max();
+    let background = |color: Rgb| format!("\x1b[48;2;{};{};{}m", color.0, color.1, color.2);
+    let mut rendered = format!("/// {} HEATMAP, MAX {}\n", kind.name().to_uppercase(), max);
+    for y in 0..heatmap.y_len() {
+        for x in 0..heatmap.x_len() {
+            let terrain = field_of_play.get_terrain(x, y);
+            if terrain.is_passable() {
+                rendered.push_str(&background(palette.heat_color(heatmap.get(x, y), max)));
+                rendered.push_str("  ");
+            } else {
+                rendered.push_str(&background(palette.obstacle));
+                rendered.push(terrain.symbol());
+                rendered.push(terrain.symbol());
+            }
+        }
+        // Reset the colour so it does not run on to the end of the line.
+        rendered.push_str("\x1b[0m\n");
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+use crate::models::rng::new_rng;
+#[cfg(test)]
+use crate::models::topology::Topology;
+
+#[test]
+fn render_field_topology_test() {
+    let mut field_of_play = FieldOfPlay::new(3, 2);
+    let players = vec![Player::new(0, true, &mut field_of_play, &mut new_rng(0))];
+    let bounded = render_field(&field_of_play, &players, 1);
+    assert_eq!(bounded.lines().count(), 3);
+    assert!(bounded.lines().skip(1).all(|line| line.len() == 3));
+
+    field_of_play.set_topology(Topology::Torus);
+    let torus = render_field(&field_of_play, &players, 1);
+    let lines: Vec<&str> = torus.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[1], " ^^^ ");
+    assert!(lines[2].starts_with('<') && lines[2].ends_with('>'));
+    assert_eq!(lines[4], " vvv ");
+}
+
+#[test]
+fn render_heatmap_test() {
+    let field_of_play = FieldOfPlay::new(3, 2);
+    let mut heatmap = Heatmap::new(3, 2);
+    heatmap.increment(1, 1);
+    let rendered = render_heatmap(&heatmap, HeatmapKind::Tags, &field_of_play);
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "/// TAGS HEATMAP, MAX 1");
+    assert_eq!(lines[2].matches("\x1b[48;2;").count(), 3);
+    // The only count is the highest, so it gets the it colour.
+    assert!(lines[2].contains("\x1b[48;2;214;40;40m"));
 }
@@ -0,0 +1,776 @@
+use crate::models::event::{Event, GameEndReason, PlayerStart};
+use crate::models::field_of_play::FieldOfPlay;
+use crate::models::player::Player;
+use crate::models::resolution::MoveResolution;
+use crate::models::rng::new_rng;
+use crate::models::rules::GameMode;
+use crate::models::terrain::Terrain;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Plays back a game from its event log one turn at a time, rebuilding the field of play from the
+/// events alone. No player logic is run. Every event is checked against the rebuilt state as it is
+/// applied, so a log that could not have come from a real game, such as one with a move into an
+/// occupied position, is reported as an error.
+pub struct Replay {
+    events: Vec<Event>,
+    // The index of the next event to apply.
+    next_event: usize,
+    field_of_play: FieldOfPlay,
+    players: Vec<Player>,
+    game_mode: GameMode,
+    move_resolution: MoveResolution,
+    num_turns: usize,
+    turn_num: usize,
+    has_ended: bool
+}
+
+// The moves made so far in the turn being replayed.
+#[derive(Default)]
+struct TurnMoves {
+    players_who_moved: HashSet<usize>,
+    // Where each player who moved away this turn started it.
+    vacated: HashMap<(usize, usize), usize>,
+    // Where they moved to.
+    arrived: HashSet<(usize, usize)>
+}
+
+impl Replay {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|err| format!("could not open event log {}: {}", path.display(), err))?;
+
+        Replay::from_reader(BufReader::new(file))
+    }
+
+    /// Reads an event log written by [JsonLinesSink](crate::models::event::JsonLinesSink). Blank
+    /// lines are skipped.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, String> {
+        let mut events = Vec::new();
+        for (line_index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| format!("line {}: {}", line_index + 1, err))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event =
+                serde_json::from_str::<Event>(&line).map_err(|err| format!("line {}: {}", line_index + 1, err))?;
+            events.push(event);
+        }
+
+        Replay::new(events)
+    }
+
+    /// Sets up the field of play from the first event, which must be [Event::GameStarted].
+    pub fn new(events: Vec<Event>) -> Result<Self, String> {
+        let (game_mode, topology, move_resolution, num_turns, x_len, y_len, terrain, player_starts) =
+            match events.first() {
+                Some(Event::GameStarted {
+                    game_mode,
+                    topology,
+                    move_resolution,
+                    num_turns,
+                    x_len,
+                    y_len,
+                    terrain,
+                    players,
+                    ..
+                }) => (
+                    *game_mode,
+                    *topology,
+                    *move_resolution,
+                    *num_turns,
+                    *x_len,
+                    *y_len,
+                    terrain,
+                    players
+                ),
+                Some(event) => return Err(format!("the event log must start with game_started, not {:?}", event)),
+                None => return Err("the event log is empty".to_owned())
+            };
+
+        let terrain = parse_terrain(terrain, x_len, y_len)?;
+        let mut field_of_play = FieldOfPlay::from_terrain(terrain);
+        field_of_play.set_topology(topology);
+        let players = place_players(player_starts, &mut field_of_play)?;
+
+        Ok(Replay {
+            events,
+            next_event: 1,
+            field_of_play,
+            players,
+            game_mode,
+            move_resolution,
+            num_turns,
+            turn_num: 0,
+            has_ended: false
+        })
+    }
+
+    /// Applies every event of the next turn. Returns false without doing anything if every turn has
+    /// been replayed, or an error describing the first event that does not fit the game so far.
+    pub fn step(&mut self) -> Result<bool, String> {
+        if self.is_finished() {
+            return Ok(false);
+        }
+
+        match self.events[self.next_event] {
+            Event::TurnStarted { turn } if turn == self.turn_num + 1 => self.turn_num = turn,
+            ref event => {
+                return Err(format!(
+                    "expected turn {} to start, found {:?}",
+                    self.turn_num + 1,
+                    event
+                ))
+            }
+        }
+        self.next_event += 1;
+
+        let mut turn_moves = TurnMoves::default();
+        while let Some(event) = self.events.get(self.next_event) {
+            if let Event::TurnStarted { .. } = event {
+                break;
+            }
+            let event = event.clone();
+            self.apply(&event, &mut turn_moves)
+                .map_err(|err| format!("turn {}: {}", self.turn_num, err))?;
+            self.next_event += 1;
+        }
+        if self.is_finished() && !self.has_ended {
+            return Err(format!("turn {}: the event log ends without game_ended", self.turn_num));
+        }
+
+        Ok(true)
+    }
+
+    /// Replays turns until `turn_num` has been replayed, or the log runs out.
+    pub fn skip_to_turn(&mut self, turn_num: usize) -> Result<(), String> {
+        while self.turn_num < turn_num && self.step()? {}
+
+        Ok(())
+    }
+
+    /// True once every event in the log has been applied.
+    pub fn is_finished(&self) -> bool {
+        self.next_event >= self.events.len()
+    }
+
+    pub fn field_of_play(&self) -> &FieldOfPlay {
+        &self.field_of_play
+    }
+
+    /// The players as of the last replayed turn. Risk tolerance is not recorded in the event log, so
+    /// it is meaningless here.
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    /// The number of turns replayed so far.
+    pub fn turn_num(&self) -> usize {
+        self.turn_num
+    }
+
+    fn apply(&mut self, event: &Event, turn_moves: &mut TurnMoves) -> Result<(), String> {
+        if self.has_ended {
+            return Err(format!("{:?} happened after the game ended", event));
+        }
+        match *event {
+            Event::GameStarted { .. } => Err("the game started a second time".to_owned()),
+            Event::TurnStarted { .. } => unreachable!("turns are started by step"),
+            Event::Moved {
+                turn,
+                player,
+                from_x,
+                from_y,
+                to_x,
+                to_y,
+                ref via
+            } => {
+                self.check_turn(turn)?;
+                self.check_player_can_move(player, (from_x, from_y), turn_moves)?;
+                for (x, y) in via.iter().copied() {
+                    if let Some(occupant) = self.get_occupant_for_move((x, y), turn_moves) {
+                        return Err(format!(
+                            "p{} moved through ({}, {}), which was occupied by p{}",
+                            player, x, y, occupant
+                        ));
+                    }
+                }
+                let mut previous = (from_x, from_y);
+                for (x, y) in via.iter().copied().chain(std::iter::once((to_x, to_y))) {
+                    if !self.field_of_play.is_adjacent(previous, (x, y)) {
+                        return Err(format!(
+                            "p{} moved from ({}, {}) to ({}, {}), which is not next to it",
+                            player, previous.0, previous.1, x, y
+                        ));
+                    }
+                    if !self.field_of_play.is_passable(x, y) {
+                        return Err(format!("p{} moved to ({}, {}), which is not open ground", player, x, y));
+                    }
+                    previous = (x, y);
+                }
+                if let Some(occupant) = self
+                    .field_of_play
+                    .get_occupant(to_x, to_y)
+                    .or_else(|| self.get_occupant_for_move((to_x, to_y), turn_moves))
+                {
+                    return Err(format!(
+                        "p{} moved to ({}, {}), which is occupied by p{}",
+                        player, to_x, to_y, occupant
+                    ));
+                }
+                self.field_of_play.set_occupant(from_x, from_y, None);
+                self.field_of_play.set_occupant(to_x, to_y, Some(player));
+                self.players[player].set_location(to_x, to_y);
+                turn_moves.vacated.insert((from_x, from_y), player);
+                turn_moves.arrived.insert((to_x, to_y));
+
+                Ok(())
+            }
+            Event::Stuck { turn, player, x, y } => {
+                self.check_turn(turn)?;
+                self.check_player_can_move(player, (x, y), turn_moves)
+            }
+            Event::Tagged {
+                turn,
+                player,
+                x,
+                y,
+                tagged_player,
+                tagged_x,
+                tagged_y
+            } => {
+                self.check_turn(turn)?;
+                self.check_player_location(player, (x, y))?;
+                self.check_player_location(tagged_player, (tagged_x, tagged_y))?;
+                if !self.players[player].is_it {
+                    return Err(format!("p{} tagged p{} without being it", player, tagged_player));
+                }
+                if self.players[tagged_player].is_it || self.players[tagged_player].is_frozen {
+                    return Err(format!(
+                        "p{} tagged p{}, who was already it or frozen",
+                        player, tagged_player
+                    ));
+                }
+                if player == tagged_player || !self.field_of_play.is_adjacent((x, y), (tagged_x, tagged_y)) {
+                    return Err(format!(
+                        "p{} tagged p{}, who is not next to them",
+                        player, tagged_player
+                    ));
+                }
+                match self.game_mode {
+                    GameMode::Classic | GameMode::Infection => {
+                        self.players[tagged_player].is_it = true;
+                        self.field_of_play.set_it(tagged_player, true);
+                        if self.game_mode == GameMode::Classic {
+                            self.players[player].is_it = false;
+                            self.field_of_play.set_it(player, false);
+                        }
+                    }
+                    GameMode::Freeze => {
+                        self.players[tagged_player].is_frozen = true;
+                        self.field_of_play.set_frozen(tagged_player, true);
+                    }
+                }
+
+                Ok(())
+            }
+            Event::Rescued {
+                turn,
+                player,
+                x,
+                y,
+                rescued_player,
+                rescued_x,
+                rescued_y
+            } => {
+                self.check_turn(turn)?;
+                self.check_player_location(player, (x, y))?;
+                self.check_player_location(rescued_player, (rescued_x, rescued_y))?;
+                if self.game_mode != GameMode::Freeze {
+                    return Err(format!("p{} rescued p{} outside of freeze tag", player, rescued_player));
+                }
+                if self.players[player].is_it || self.players[player].is_frozen {
+                    return Err(format!(
+                        "p{} rescued p{} while it or frozen themselves",
+                        player, rescued_player
+                    ));
+                }
+                if !self.players[rescued_player].is_frozen {
+                    return Err(format!("p{} rescued p{}, who was not frozen", player, rescued_player));
+                }
+                if !self.field_of_play.is_adjacent((x, y), (rescued_x, rescued_y)) {
+                    return Err(format!(
+                        "p{} rescued p{}, who is not next to them",
+                        player, rescued_player
+                    ));
+                }
+                self.players[rescued_player].is_frozen = false;
+                self.field_of_play.set_frozen(rescued_player, false);
+
+                Ok(())
+            }
+            Event::GameEnded { turn, reason } => {
+                self.check_turn(turn)?;
+                self.check_game_end_reason(reason)?;
+                self.has_ended = true;
+
+                Ok(())
+            }
+        }
+    }
+
+    fn check_turn(&self, turn: usize) -> Result<(), String> {
+        if turn != self.turn_num {
+            return Err(format!("found an event for turn {}", turn));
+        }
+
+        Ok(())
+    }
+
+    fn check_player_location(&self, player: usize, location: (usize, usize)) -> Result<(), String> {
+        let recorded_player = self
+            .players
+            .get(player)
+            .ok_or_else(|| format!("there is no player p{}", player))?;
+        if recorded_player.get_location() != location {
+            return Err(format!(
+                "p{} is at {:?}, not {:?}",
+                player,
+                recorded_player.get_location(),
+                location
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Players get at most one move a turn and frozen players can not move at all.
+    fn check_player_can_move(
+        &self,
+        player: usize,
+        location: (usize, usize),
+        turn_moves: &mut TurnMoves
+    ) -> Result<(), String> {
+        self.check_player_location(player, location)?;
+        if self.players[player].is_frozen {
+            return Err(format!("p{} moved while frozen", player));
+        }
+        if !turn_moves.players_who_moved.insert(player) {
+            return Err(format!("p{} moved more than once", player));
+        }
+
+        Ok(())
+    }
+
+    // The player in the way of a move passing through (x, y), if there is one. Sequential players see
+    // the moves made before theirs, so the position must be empty now. Simultaneous players decide on
+    // the field as it was at the start of the turn, so it must have been empty then, but someone
+    // else may have moved onto it since.
+    fn get_occupant_for_move(&self, (x, y): (usize, usize), turn_moves: &TurnMoves) -> Option<usize> {
+        let occupant = self.field_of_play.get_occupant(x, y);
+        match self.move_resolution {
+            MoveResolution::Sequential => occupant,
+            MoveResolution::Simultaneous => turn_moves
+                .vacated
+                .get(&(x, y))
+                .copied()
+                .or_else(|| occupant.filter(|_| !turn_moves.arrived.contains(&(x, y))))
+        }
+    }
+
+    fn check_game_end_reason(&self, reason: GameEndReason) -> Result<(), String> {
+        let is_consistent = match reason {
+            GameEndReason::TurnLimit => self.turn_num == self.num_turns,
+            GameEndReason::AllFrozen => self
+                .players
+                .iter()
+                .filter(|player| !player.is_it)
+                .all(|player| player.is_frozen),
+            GameEndReason::AllInfected => self.players.iter().all(|player| player.is_it)
+        };
+        if !is_consistent {
+            return Err(format!("the game ended with {:?} but that had not happened", reason));
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_terrain(rows: &[String], x_len: usize, y_len: usize) -> Result<Vec<Vec<Terrain>>, String> {
+    if x_len == 0 || rows.len() != y_len {
+        return Err(format!(
+            "the field is {} by {} but has {} rows of terrain",
+            x_len,
+            y_len,
+            rows.len()
+        ));
+    }
+
+    rows.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            let y_axis: Vec<Terrain> = row
+                .chars()
+                .map(|symbol| {
+                    Terrain::from_symbol(symbol)
+                        .ok_or_else(|| format!("unknown terrain symbol '{}' in row {}", symbol, y))
+                })
+                .collect::<Result<_, _>>()?;
+            if y_axis.len() != x_len {
+                return Err(format!("row {} of the terrain is not {} long", y, x_len));
+            }
+
+            Ok(y_axis)
+        })
+        .collect()
+}
+
+fn place_players(player_starts: &[PlayerStart], field_of_play: &mut FieldOfPlay) -> Result<Vec<Player>, String> {
+    // Risk tolerance is not in the event log, so it does not matter what the random number generator
+    // gives the players.
+    let mut rng = new_rng(0);
+    let mut players = Vec::new();
+    for (index, start) in player_starts.iter().enumerate() {
+        if start.player != index {
+            return Err(format!("expected p{} to start next, not p{}", index, start.player));
+        }
+        if !field_of_play.is_passable(start.x, start.y) || field_of_play.get_occupant(start.x, start.y).is_some() {
+            return Err(format!(
+                "p{} can not start at ({}, {}) because it is not empty open ground",
+                index, start.x, start.y
+            ));
+        }
+        players.push(Player::new_at_one_of(
+            index,
+            start.is_it,
+            &[(start.x, start.y)],
+            field_of_play,
+            &mut rng
+        ));
+    }
+
+    Ok(players)
+}
+
+#[cfg(test)]
+use crate::models::topology::Topology;
+#[cfg(test)]
+use crate::simulation::{Simulation, SimulationConfig};
+#[cfg(test)]
+use crate::ChaserStrategy;
+#[cfg(test)]
+use std::sync::mpsc::{channel, Receiver};
+
+#[cfg(test)]
+fn recorded_simulation(game_mode: GameMode) -> (Simulation, Receiver<Event>) {
+    let config = SimulationConfig {
+        num_players: 6,
+        x_axis_len: 8,
+        y_axis_len: 8,
+        num_turns: 60,
+        seed: Some(4),
+        chaser_strategy: ChaserStrategy::Nearest,
+        game_mode,
+        obstacle_density: 0.1,
+        ..SimulationConfig::default()
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+    let (sender, receiver) = channel();
+    simulation.add_event_sink(Box::new(sender));
+
+    (simulation, receiver)
+}
+
+// A game on a 4 by 1 field with p0, who is it, at the west end and p1 at the east end.
+#[cfg(test)]
+fn small_game(first_turn_events: Vec<Event>) -> Vec<Event> {
+    let mut events = vec![
+        Event::GameStarted {
+            seed: 0,
+            game_mode: GameMode::Classic,
+            topology: Topology::Bounded,
+            move_resolution: MoveResolution::Sequential,
+            num_turns: 10,
+            x_len: 4,
+            y_len: 1,
+            terrain: vec!["....".to_owned()],
+            players: vec![
+                PlayerStart {
+                    player: 0,
+                    x: 0,
+                    y: 0,
+                    is_it: true
+                },
+                PlayerStart {
+                    player: 1,
+                    x: 3,
+                    y: 0,
+                    is_it: false
+                },
+            ]
+        },
+        Event::TurnStarted { turn: 1 },
+    ];
+    events.extend(first_turn_events);
+
+    events
+}
+
+#[cfg(test)]
+fn two_row_game(move_resolution: MoveResolution, first_turn_events: Vec<Event>) -> Vec<Event> {
+    let mut events = vec![
+        Event::GameStarted {
+            seed: 0,
+            game_mode: GameMode::Classic,
+            topology: Topology::Bounded,
+            move_resolution,
+            num_turns: 10,
+            x_len: 5,
+            y_len: 2,
+            terrain: vec![".....".to_owned(), ".....".to_owned()],
+            players: vec![
+                PlayerStart {
+                    player: 0,
+                    x: 0,
+                    y: 0,
+                    is_it: true
+                },
+                PlayerStart {
+                    player: 1,
+                    x: 2,
+                    y: 0,
+                    is_it: false
+                },
+            ]
+        },
+        Event::TurnStarted { turn: 1 },
+    ];
+    events.extend(first_turn_events);
+    events.push(Event::TurnStarted { turn: 2 });
+
+    events
+}
+
+#[test]
+fn replay_checks_every_position_a_move_passes_through_test() {
+    // p1 passes through the position p0 has just moved onto.
+    let through_arrival = vec![
+        Event::Moved {
+            turn: 1,
+            player: 0,
+            from_x: 0,
+            from_y: 0,
+            to_x: 1,
+            to_y: 0,
+            via: vec![]
+        },
+        Event::Moved {
+            turn: 1,
+            player: 1,
+            from_x: 2,
+            from_y: 0,
+            to_x: 0,
+            to_y: 1,
+            via: vec![(1, 0)]
+        },
+    ];
+    assert_eq!(
+        Replay::new(two_row_game(MoveResolution::Sequential, through_arrival.clone()))
+            .unwrap()
+            .step()
+            .unwrap_err(),
+        "turn 1: p1 moved through (1, 0), which was occupied by p0"
+    );
+    // Simultaneous players only see where everyone started the turn.
+    assert!(Replay::new(two_row_game(MoveResolution::Simultaneous, through_arrival))
+        .unwrap()
+        .step()
+        .unwrap());
+
+    // p1 passes through, or ends on, the position p0 has just left.
+    let mut through_departure = vec![
+        Event::Moved {
+            turn: 1,
+            player: 0,
+            from_x: 0,
+            from_y: 0,
+            to_x: 0,
+            to_y: 1,
+            via: vec![]
+        },
+        Event::Moved {
+            turn: 1,
+            player: 1,
+            from_x: 2,
+            from_y: 0,
+            to_x: 1,
+            to_y: 1,
+            via: vec![(1, 0), (0, 0)]
+        },
+    ];
+    assert!(
+        Replay::new(two_row_game(MoveResolution::Sequential, through_departure.clone()))
+            .unwrap()
+            .step()
+            .unwrap()
+    );
+    assert_eq!(
+        Replay::new(two_row_game(MoveResolution::Simultaneous, through_departure.clone()))
+            .unwrap()
+            .step()
+            .unwrap_err(),
+        "turn 1: p1 moved through (0, 0), which was occupied by p0"
+    );
+    through_departure[1] = Event::Moved {
+        turn: 1,
+        player: 1,
+        from_x: 2,
+        from_y: 0,
+        to_x: 0,
+        to_y: 0,
+        via: vec![(1, 0)]
+    };
+    assert_eq!(
+        Replay::new(two_row_game(MoveResolution::Simultaneous, through_departure))
+            .unwrap()
+            .step()
+            .unwrap_err(),
+        "turn 1: p1 moved to (0, 0), which is occupied by p0"
+    );
+}
+
+#[test]
+fn replay_matches_simulation_test() {
+    [GameMode::Classic, GameMode::Freeze, GameMode::Infection]
+        .iter()
+        .for_each(|game_mode| {
+            let (recorded, receiver) = recorded_simulation(*game_mode);
+            recorded.run();
+            let mut replay = Replay::new(receiver.try_iter().collect()).unwrap();
+            // The same seed plays the same game again, so it can be compared with the replay turn by
+            // turn.
+            let (mut simulation, _) = recorded_simulation(*game_mode);
+
+            while simulation.step() {
+                assert!(replay.step().unwrap());
+                assert_eq!(replay.turn_num(), simulation.turn_num());
+                assert_eq!(
+                    replay.field_of_play().get_player_positions(),
+                    simulation.field_of_play().get_player_positions()
+                );
+                replay
+                    .players()
+                    .iter()
+                    .zip(simulation.players())
+                    .for_each(|(replayed, played)| {
+                        assert_eq!(replayed.is_it, played.is_it);
+                        assert_eq!(replayed.is_frozen, played.is_frozen);
+                    });
+            }
+            assert!(replay.is_finished());
+        });
+}
+
+#[test]
+fn replay_skip_to_turn_test() {
+    let (simulation, receiver) = recorded_simulation(GameMode::Classic);
+    simulation.run();
+    let mut replay = Replay::new(receiver.try_iter().collect()).unwrap();
+
+    replay.skip_to_turn(25).unwrap();
+    assert_eq!(replay.turn_num(), 25);
+    replay.skip_to_turn(1000).unwrap();
+    assert_eq!(replay.turn_num(), 60);
+    assert!(replay.is_finished());
+    assert!(!replay.step().unwrap());
+}
+
+#[test]
+fn replay_detects_inconsistent_log_test() {
+    let moved_into_occupied = small_game(vec![
+        Event::Stuck {
+            turn: 1,
+            player: 0,
+            x: 0,
+            y: 0
+        },
+        Event::Moved {
+            turn: 1,
+            player: 1,
+            from_x: 3,
+            from_y: 0,
+            to_x: 2,
+            to_y: 0,
+            via: Vec::new()
+        },
+        Event::TurnStarted { turn: 2 },
+        Event::Moved {
+            turn: 2,
+            player: 0,
+            from_x: 0,
+            from_y: 0,
+            to_x: 1,
+            to_y: 0,
+            via: Vec::new()
+        },
+        Event::Moved {
+            turn: 2,
+            player: 1,
+            from_x: 2,
+            from_y: 0,
+            to_x: 1,
+            to_y: 0,
+            via: Vec::new()
+        },
+    ]);
+    let mut replay = Replay::new(moved_into_occupied).unwrap();
+    assert!(replay.step().unwrap());
+    assert_eq!(
+        replay.step().unwrap_err(),
+        "turn 2: p1 moved to (1, 0), which is occupied by p0"
+    );
+
+    let skipped_a_position = small_game(vec![Event::Moved {
+        turn: 1,
+        player: 1,
+        from_x: 3,
+        from_y: 0,
+        to_x: 0,
+        to_y: 0,
+        via: vec![(2, 0)]
+    }]);
+    assert_eq!(
+        Replay::new(skipped_a_position).unwrap().step().unwrap_err(),
+        "turn 1: p1 moved from (2, 0) to (0, 0), which is not next to it"
+    );
+
+    let distant_tag = small_game(vec![Event::Tagged {
+        turn: 1,
+        player: 0,
+        x: 0,
+        y: 0,
+        tagged_player: 1,
+        tagged_x: 3,
+        tagged_y: 0
+    }]);
+    assert_eq!(
+        Replay::new(distant_tag).unwrap().step().unwrap_err(),
+        "turn 1: p0 tagged p1, who is not next to them"
+    );
+
+    let truncated = small_game(vec![Event::Stuck {
+        turn: 1,
+        player: 0,
+        x: 0,
+        y: 0
+    }]);
+    assert_eq!(
+        Replay::new(truncated).unwrap().step().unwrap_err(),
+        "turn 1: the event log ends without game_ended"
+    );
+}
@@ -0,0 +1,1546 @@
+use crate::models::action::{Action, ActionType};
+use crate::models::arena::Arena;
+use crate::models::direction::Direction;
+use crate::models::event::{Event, EventSink, GameEndReason, PlayerStart};
+use crate::models::field_of_play::FieldOfPlay;
+use crate::models::player::Player;
+use crate::models::pursuit::ChaserStrategy;
+use crate::models::resolution::{is_swap, CollisionRule, Conflict, MoveResolution};
+use crate::models::rng::{new_rng, SimRng};
+use crate::models::rules::GameMode;
+use crate::models::stats::{MoveRecord, Stats};
+use crate::models::strategy::{MovementStrategy, Strategies};
+use crate::models::topology::Topology;
+use crate::models::turn_order::TurnOrder;
+use crate::models::vision::Vision;
+use log::{debug, info};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+const GENERIC_ACTION_PANIC_MSG: &str = "Invalid action param";
+
+/// Everything needed to set up a game of tag. If `seed` is `None` a random seed is chosen; it can be
+/// read back with [Simulation::seed] to reproduce the run.
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub num_players: usize,
+    /// Ignored when an `arena` is given.
+    pub x_axis_len: usize,
+    /// Ignored when an `arena` is given.
+    pub y_axis_len: usize,
+    pub num_turns: usize,
+    pub seed: Option<u64>,
+    pub chaser_strategy: ChaserStrategy,
+    pub game_mode: GameMode,
+    /// How many players start the game as it. Players 0 to `num_it_players - 1` are it.
+    pub num_it_players: usize,
+    /// How many turns a tagged player must wait before tagging the player who tagged them. With
+    /// `None` they can not tag them back until someone else tags them.
+    pub tag_back_cooldown: Option<usize>,
+    /// The chance, between 0.0 and 1.0, of each position on the field being an obstacle.
+    pub obstacle_density: f64,
+    /// Plays on a field loaded from a map instead of an open field of `x_axis_len` by `y_axis_len`.
+    pub arena: Option<Arena>,
+    /// Whether players can walk off the edges of the field and come back on the other side.
+    pub topology: Topology,
+    /// The order players act in during each turn.
+    pub turn_order: TurnOrder,
+    /// Whether players act one after the other or all at once.
+    pub move_resolution: MoveResolution,
+    /// Who gets to move when simultaneous moves collide. Only used with
+    /// [MoveResolution::Simultaneous].
+    pub collision_rule: CollisionRule,
+    /// The most positions a player can move in a turn. Every position after the first costs a
+    /// stamina.
+    pub max_speed: usize,
+    /// How much stamina players start with, and the most they can have.
+    pub max_stamina: usize,
+    /// Gives every player a random max speed between 1 and `max_speed` and a random max stamina
+    /// between 0 and `max_stamina` instead.
+    pub random_fitness: bool,
+    /// Limits what players can see to a cone in front of them. Without vision every player always
+    /// knows where the it player was last seen.
+    pub vision: Option<Vision>
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            num_players: 10,
+            x_axis_len: 50,
+            y_axis_len: 50,
+            num_turns: 1000,
+            seed: None,
+            chaser_strategy: ChaserStrategy::default(),
+            game_mode: GameMode::default(),
+            num_it_players: 1,
+            tag_back_cooldown: None,
+            obstacle_density: 0.0,
+            arena: None,
+            topology: Topology::default(),
+            turn_order: TurnOrder::default(),
+            move_resolution: MoveResolution::default(),
+            collision_rule: CollisionRule::default(),
+            max_speed: 1,
+            max_stamina: 10,
+            random_fitness: false,
+            vision: None
+        }
+    }
+}
+
+/// A game of tag that is advanced one turn at a time by the caller. The simulation never sleeps or
+/// prints, so it is up to the caller to decide how, or if, each turn is displayed.
+pub struct Simulation {
+    field_of_play_cache: FieldOfPlay,
+    players: Vec<Player>,
+    stats: Stats,
+    rng: SimRng,
+    strategies: Strategies,
+    game_mode: GameMode,
+    tag_back_cooldown: Option<usize>,
+    seed: u64,
+    num_turns: usize,
+    turn_num: usize,
+    // Set when the game ends before the turn limit, such as when everyone is frozen in freeze tag or
+    // infected in infection.
+    game_over: bool,
+    event_sinks: Vec<Box<dyn EventSink>>,
+    turn_order: TurnOrder,
+    // Only used with TurnOrder::Initiative.
+    initiative: Vec<usize>,
+    move_resolution: MoveResolution,
+    collision_rule: CollisionRule,
+    config: SimulationConfig
+}
+
+impl Simulation {
+    pub fn new(config: SimulationConfig) -> Result<Self, String> {
+        if config.arena.is_none() && (config.x_axis_len == 0 || config.y_axis_len == 0) {
+            return Err("the field of play must have at least one row and one column".to_owned());
+        }
+        if config.arena.is_some() && config.obstacle_density > 0.0 {
+            return Err("obstacles can not be generated on a field loaded from a map".to_owned());
+        }
+        if !(0.0..=1.0).contains(&config.obstacle_density) {
+            return Err("the obstacle density must be between 0.0 and 1.0".to_owned());
+        }
+        if config.max_speed == 0 {
+            return Err("the max speed must be at least 1".to_owned());
+        }
+        if let Some(vision) = &config.vision {
+            vision.validate()?;
+        }
+        if config.num_players > FieldOfPlay::MAX_PLAYERS {
+            return Err(format!("there can be at most {} players", FieldOfPlay::MAX_PLAYERS));
+        }
+        if config.num_it_players == 0 || config.num_it_players > config.num_players {
+            return Err(format!(
+                "the number of it players must be between 1 and the number of players ({})",
+                config.num_players
+            ));
+        }
+
+        if let Some(arena) = &config.arena {
+            arena.validate_player_count(config.num_players, config.num_it_players)?;
+        }
+        let (x_axis_len, y_axis_len) = config
+            .arena
+            .as_ref()
+            .map_or((config.x_axis_len, config.y_axis_len), |arena| {
+                (arena.x_len(), arena.y_len())
+            });
+
+        let seed = config.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        info!(
+            "Initalizing game with num players: {}, x-axis size: {}, y-axis size: {}, seed: {}",
+            config.num_players, x_axis_len, y_axis_len, seed
+        );
+
+        let mut rng = new_rng(seed);
+        let mut field_of_play_cache = match &config.arena {
+            Some(arena) => arena.build_field_of_play(),
+            None => FieldOfPlay::new(x_axis_len, y_axis_len)
+        };
+        // On an axis shorter than 3 a player's neighbours would wrap round onto each other or the
+        // player themselves.
+        if (config.topology.wraps_x() && x_axis_len < 3) || (config.topology.wraps_y() && y_axis_len < 3) {
+            return Err("an axis must be at least 3 long to wrap around".to_owned());
+        }
+        field_of_play_cache.set_topology(config.topology);
+        field_of_play_cache.generate_obstacles(config.obstacle_density, &mut rng);
+        if field_of_play_cache.count_passable_positions() < config.num_players {
+            return Err(format!(
+                "{} players cannot fit on field of dimensions {} x {} with {} open positions",
+                config.num_players,
+                x_axis_len,
+                y_axis_len,
+                field_of_play_cache.count_passable_positions()
+            ));
+        }
+        let mut players: Vec<Player> = (0..config.num_players)
+            .map(|player_num| {
+                let is_it = player_num < config.num_it_players;
+                let start_positions = match &config.arena {
+                    Some(arena) if is_it => arena.it_start_points(),
+                    Some(arena) => arena.spawn_points(),
+                    None => &[]
+                };
+                Player::new_at_one_of(player_num, is_it, start_positions, &mut field_of_play_cache, &mut rng)
+            })
+            .collect();
+        for player in players.iter_mut() {
+            if config.random_fitness {
+                let max_speed = rng.gen_range(1..=config.max_speed);
+                let max_stamina = rng.gen_range(0..=config.max_stamina);
+                player.set_fitness(max_speed, max_stamina);
+            } else {
+                player.set_fitness(config.max_speed, config.max_stamina);
+            }
+            if let Some(vision) = config.vision {
+                player.set_vision(vision);
+                player.set_facing(*Direction::ALL.choose(&mut rng).expect("There is always a direction."));
+            }
+        }
+        let mut stats = Stats::new(&players, config.num_turns, x_axis_len, y_axis_len);
+        let strategies = Strategies {
+            chaser: config.chaser_strategy.build(),
+            ..Strategies::default()
+        };
+        stats.set_chaser_strategy(strategies.chaser.name());
+        stats.set_game_mode(config.game_mode);
+        stats.set_turn_order(config.turn_order);
+        stats.set_move_resolution(config.move_resolution, config.collision_rule);
+        stats.set_vision(config.vision);
+        let initiative = config.turn_order.roll_initiative(players.len(), &mut rng);
+
+        Ok(Simulation {
+            field_of_play_cache,
+            players,
+            stats,
+            rng,
+            strategies,
+            game_mode: config.game_mode,
+            tag_back_cooldown: config.tag_back_cooldown,
+            seed,
+            num_turns: config.num_turns,
+            turn_num: 0,
+            game_over: false,
+            event_sinks: Vec::new(),
+            turn_order: config.turn_order,
+            initiative,
+            move_resolution: config.move_resolution,
+            collision_rule: config.collision_rule,
+            config: SimulationConfig {
+                seed: Some(seed),
+                ..config
+            }
+        })
+    }
+
+    /// Replaces the strategy players who are not it use to decide where to move. The default is
+    /// [RandomAvoidanceStrategy](crate::models::strategy::RandomAvoidanceStrategy).
+    pub fn set_runner_strategy(&mut self, runner_strategy: Box<dyn MovementStrategy>) {
+        self.strategies.runner = runner_strategy;
+    }
+
+    /// Replaces the strategy the it player uses to decide where to move. This overrides the chaser
+    /// strategy from the [SimulationConfig].
+    pub fn set_chaser_strategy(&mut self, chaser_strategy: Box<dyn MovementStrategy>) {
+        self.stats.set_chaser_strategy(chaser_strategy.name());
+        self.strategies.chaser = chaser_strategy;
+    }
+
+    /// Sends every event from now on to `event_sink`, as well as any sinks added before. Add it
+    /// before the first turn to receive the [Event::GameStarted] event, which describes the starting
+    /// state of the game.
+    pub fn add_event_sink(&mut self, event_sink: Box<dyn EventSink>) {
+        self.event_sinks.push(event_sink);
+    }
+
+    /// Plays a single turn, giving every player a chance to act. Returns false without doing
+    /// anything if the simulation has already finished.
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        if self.turn_num == 0 && !self.event_sinks.is_empty() {
+            let game_started = self.game_started_event();
+            emit(&mut self.event_sinks, game_started);
+        }
+        self.turn_num += 1;
+        emit(&mut self.event_sinks, Event::TurnStarted { turn: self.turn_num });
+        self.players_take_action();
+        self.record_distances_from_it();
+        self.stats.record_positions(&self.players);
+        self.stats.record_turn_played(self.turn_num);
+        let it_player_count = self.players.iter().filter(|player| player.is_it).count();
+        self.stats.record_it_player_count(it_player_count);
+        if self.game_mode == GameMode::Freeze && self.are_all_runners_frozen() {
+            info!("Every player has been frozen on turn {}", self.turn_num);
+            self.stats.record_all_frozen(self.turn_num);
+            self.game_over = true;
+        }
+        if self.game_mode == GameMode::Infection && it_player_count == self.players.len() {
+            info!("Every player has been infected on turn {}", self.turn_num);
+            self.stats.record_all_infected(self.turn_num);
+            self.game_over = true;
+        }
+        if self.is_finished() {
+            let reason = if self.stats.all_frozen_turn().is_some() {
+                GameEndReason::AllFrozen
+            } else if self.stats.all_infected_turn().is_some() {
+                GameEndReason::AllInfected
+            } else {
+                GameEndReason::TurnLimit
+            };
+            emit(
+                &mut self.event_sinks,
+                Event::GameEnded {
+                    turn: self.turn_num,
+                    reason
+                }
+            );
+        }
+
+        true
+    }
+
+    /// Plays turns until `should_stop` returns true or the simulation finishes. `should_stop` is
+    /// checked before every turn.
+    pub fn run_until<F>(&mut self, mut should_stop: F)
+    where
+        F: FnMut(&Simulation) -> bool
+    {
+        while !should_stop(self) && self.step() {}
+    }
+
+    /// Plays every remaining turn and returns the stats for the whole game.
+    pub fn run(mut self) -> Stats {
+        self.run_until(|_| false);
+
+        self.into_stats()
+    }
+
+    /// True once the turn limit is reached or the game mode's end condition is met.
+    pub fn is_finished(&self) -> bool {
+        self.game_over || self.turn_num >= self.num_turns
+    }
+
+    pub fn game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    pub fn field_of_play(&self) -> &FieldOfPlay {
+        &self.field_of_play_cache
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    pub fn into_stats(self) -> Stats {
+        self.stats
+    }
+
+    /// The number of turns played so far.
+    pub fn turn_num(&self) -> usize {
+        self.turn_num
+    }
+
+    /// The config the simulation was created with. The seed is always set, to the random one that
+    /// was chosen if the config did not have one.
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn game_started_event(&self) -> Event {
+        let field_of_play = &self.field_of_play_cache;
+        let terrain = (0..field_of_play.y_len())
+            .map(|y| {
+                (0..field_of_play.x_len())
+                    .map(|x| field_of_play.get_terrain(x, y).symbol())
+                    .collect()
+            })
+            .collect();
+        let players = self
+            .players
+            .iter()
+            .map(|player| {
+                let (x, y) = player.get_location();
+                PlayerStart {
+                    player: player.get_index(),
+                    x,
+                    y,
+                    is_it: player.is_it
+                }
+            })
+            .collect();
+
+        Event::GameStarted {
+            seed: self.seed,
+            game_mode: self.game_mode,
+            topology: field_of_play.topology(),
+            move_resolution: self.move_resolution,
+            num_turns: self.num_turns,
+            x_len: field_of_play.x_len(),
+            y_len: field_of_play.y_len(),
+            terrain,
+            players
+        }
+    }
+
+    fn are_all_runners_frozen(&self) -> bool {
+        self.players
+            .iter()
+            .filter(|player| !player.is_it)
+            .all(|player| player.is_frozen)
+    }
+
+    fn record_distances_from_it(&mut self) {
+        let it_locations: Vec<(usize, usize)> = self
+            .players
+            .iter()
+            .filter(|player| player.is_it)
+            .map(|player| player.get_location())
+            .collect();
+        let field_of_play = &self.field_of_play_cache;
+        let distances: Vec<Option<usize>> = self
+            .players
+            .par_iter()
+            .map(|player| {
+                if player.is_it {
+                    return None;
+                }
+                it_locations
+                    .iter()
+                    .map(|it_location| {
+                        let (x_distance, y_distance) =
+                            field_of_play.get_axis_distances(player.get_location(), *it_location);
+                        x_distance.max(y_distance)
+                    })
+                    .min()
+            })
+            .collect();
+        self.stats.record_distances_from_it(&distances);
+    }
+
+    fn players_take_action(&mut self) {
+        let order = self.turn_order.order(&self.players, &self.initiative, &mut self.rng);
+        self.strategies.start_turn(&self.field_of_play_cache);
+        match self.move_resolution {
+            MoveResolution::Sequential => self.players_act_in_turn(order),
+            MoveResolution::Simultaneous => self.players_act_simultaneously(order)
+        }
+    }
+
+    // Each player acts on the field as the players before them left it.
+    fn players_act_in_turn(&mut self, order: Vec<usize>) {
+        for (turn_position, player_index) in order.into_iter().enumerate() {
+            self.start_player_turn(player_index, turn_position);
+            let (old_x, old_y) = self.players[player_index].get_location();
+            let it_locations = self.it_locations();
+            self.look_for_it(player_index, &it_locations);
+            let actions = self.players[player_index].take_action(
+                &self.field_of_play_cache,
+                self.game_mode,
+                &self.strategies,
+                &mut self.rng
+            );
+            debug!(
+                "player at index: {} is acting. old_x: {}, old_y: {}, actions: {:?}",
+                player_index, old_x, old_y, actions
+            );
+            for action in actions {
+                match action.action {
+                    ActionType::Move => {
+                        let new_x = action.x_coordinate.expect(GENERIC_ACTION_PANIC_MSG);
+                        let new_y = action.y_coordinate.expect(GENERIC_ACTION_PANIC_MSG);
+                        self.move_player(player_index, (old_x, old_y), (new_x, new_y), action.via);
+                    }
+                    ActionType::Tag => {
+                        let tagged_index = action.new_it_index.expect(GENERIC_ACTION_PANIC_MSG);
+                        self.tag_player(player_index, (old_x, old_y), tagged_index);
+                    }
+                    ActionType::Rescue => {
+                        let rescued_index = action.rescued_index.expect(GENERIC_ACTION_PANIC_MSG);
+                        self.rescue_player(player_index, (old_x, old_y), rescued_index);
+                    }
+                }
+            }
+        }
+    }
+
+    // Every player decides what to do on the field as it was at the start of the turn. Tags and
+    // rescues are carried out first, in turn order, then every move at once.
+    //
+    // Nothing changes the field while players decide, so they all share it as a read-only snapshot
+    // and decide in parallel across the threads of the current Rayon thread pool. Everything else
+    // that is done for every player, rather than in turn order, is done in parallel too.
+    fn players_act_simultaneously(&mut self, order: Vec<usize>) {
+        let starts: Vec<(usize, usize)> = self.players.iter().map(|player| player.get_location()).collect();
+        let mut turn_positions = vec![0; self.players.len()];
+        for (turn_position, player_index) in order.iter().enumerate() {
+            self.reveal_it_location(*player_index);
+            turn_positions[*player_index] = turn_position;
+        }
+        let turn_num = self.turn_num;
+        self.players
+            .par_iter_mut()
+            .for_each(|player| player.expire_tag_back_cooldowns(turn_num));
+        self.stats.record_every_player_start(&self.players, &turn_positions);
+
+        // Every player gets a stream of random numbers of their own for the turn, so what they
+        // decide does not depend on which thread decides it or when.
+        let turn_seed: u64 = self.rng.gen();
+        let it_locations = self.it_locations();
+        let field_of_play = &self.field_of_play_cache;
+        let game_mode = self.game_mode;
+        let strategies = &self.strategies;
+        let (mut decisions, sightings): (Vec<Vec<Action>>, Vec<bool>) = self
+            .players
+            .par_iter_mut()
+            .map(|player| {
+                let mut rng = new_rng(turn_seed);
+                rng.set_stream(player.get_index() as u64);
+                let (start_x, start_y) = player.get_location();
+                let saw_it = player.look_for_it(field_of_play, &it_locations);
+                let actions = player.take_action(field_of_play, game_mode, strategies, &mut rng);
+                debug!(
+                    "player at index: {} decided on actions: {:?}",
+                    player.get_index(),
+                    actions
+                );
+                // Nobody moves until everyone has decided.
+                player.set_location(start_x, start_y);
+                (actions, saw_it)
+            })
+            .unzip();
+        self.stats.record_every_player_sighting(&sightings);
+
+        let mut moves = Vec::new();
+        let mut vias = vec![Vec::new(); self.players.len()];
+        for player_index in order {
+            for action in std::mem::take(&mut decisions[player_index]) {
+                match action.action {
+                    ActionType::Move => {
+                        let new_x = action.x_coordinate.expect(GENERIC_ACTION_PANIC_MSG);
+                        let new_y = action.y_coordinate.expect(GENERIC_ACTION_PANIC_MSG);
+                        moves.push((player_index, (new_x, new_y)));
+                        vias[player_index] = action.via;
+                    }
+                    ActionType::Tag => {
+                        let tagged_index = action.new_it_index.expect(GENERIC_ACTION_PANIC_MSG);
+                        let tagged_player = &self.players[tagged_index];
+                        if tagged_player.is_it || tagged_player.is_frozen {
+                            // Someone earlier in the turn got to them first. In classic the tagger
+                            // gave up being it when they decided to tag, so they get it back.
+                            self.players[player_index].is_it = true;
+                        } else {
+                            self.tag_player(player_index, starts[player_index], tagged_index);
+                        }
+                    }
+                    ActionType::Rescue => {
+                        let rescued_index = action.rescued_index.expect(GENERIC_ACTION_PANIC_MSG);
+                        // The rescuer may have been frozen, or the frozen player rescued, earlier in
+                        // the turn.
+                        if !self.players[player_index].is_frozen && self.players[rescued_index].is_frozen {
+                            self.rescue_player(player_index, starts[player_index], rescued_index);
+                        }
+                    }
+                }
+            }
+        }
+        // Players frozen earlier in the turn stay where they are.
+        moves.retain(|(player_index, _)| !self.players[*player_index].is_frozen);
+
+        let allowed = self.resolve_collisions(&moves, &starts);
+        let mut destinations = vec![None; self.players.len()];
+        let mut stopped = vec![false; self.players.len()];
+        for ((player_index, to), allowed) in moves.iter().zip(allowed) {
+            destinations[*player_index] = Some(if allowed { *to } else { starts[*player_index] });
+            stopped[*player_index] = !allowed;
+        }
+
+        // Every move ends on a position that was empty at the start of the turn, and no two end on
+        // the same one, so emptying every start and then filling every destination puts each player
+        // in one place without any move getting in the way of another.
+        for (player_index, _) in moves.iter() {
+            let (x, y) = starts[*player_index];
+            self.field_of_play_cache.set_occupant(x, y, None);
+        }
+        for (player_index, _) in moves.iter() {
+            if let Some((x, y)) = destinations[*player_index] {
+                self.field_of_play_cache.set_occupant(x, y, Some(*player_index));
+            }
+        }
+        let field_of_play = &self.field_of_play_cache;
+        let records: Vec<Option<MoveRecord>> = self
+            .players
+            .par_iter_mut()
+            .zip(destinations.par_iter())
+            .zip(vias.par_iter())
+            .zip(stopped.par_iter())
+            .map(|(((player, destination), via), is_stopped)| {
+                destination.map(|to| {
+                    if *is_stopped {
+                        stop(player, via)
+                    } else {
+                        move_to(field_of_play, player, starts[player.get_index()], to, via)
+                    }
+                })
+            })
+            .collect();
+        self.stats.record_every_player_move(&records);
+        for (player_index, _) in moves {
+            if let Some(to) = destinations[player_index] {
+                let via = std::mem::take(&mut vias[player_index]);
+                let event = move_event(self.turn_num, player_index, starts[player_index], to, via);
+                emit(&mut self.event_sinks, event);
+            }
+        }
+    }
+
+    // Settles simultaneous moves that collide according to the collision rule. Returns whether each
+    // move in `moves` may go ahead.
+    fn resolve_collisions(&mut self, moves: &[(usize, (usize, usize))], starts: &[(usize, usize)]) -> Vec<bool> {
+        let mut allowed = vec![true; moves.len()];
+        // Sorted by target and then by move so collisions are settled in the same order for the same
+        // seed.
+        let mut by_target: Vec<((usize, usize), usize)> = moves
+            .iter()
+            .enumerate()
+            .filter(|(_, (player_index, to))| *to != starts[*player_index])
+            .map(|(move_index, (_, to))| (*to, move_index))
+            .collect();
+        by_target.par_sort_unstable();
+        for same_target in by_target
+            .chunk_by(|a, b| a.0 == b.0)
+            .filter(|same_target| same_target.len() > 1)
+        {
+            let contenders: Vec<usize> = same_target.iter().map(|(_, move_index)| *move_index).collect();
+            self.settle_conflict(Conflict::SameCell, &contenders, moves, &mut allowed);
+        }
+
+        // Only players who start next to each other can move through each other. Nobody has moved
+        // yet, so the field still shows where everyone started.
+        let mut move_indices = vec![None; self.players.len()];
+        for (move_index, (player_index, _)) in moves.iter().enumerate() {
+            move_indices[*player_index] = Some(move_index);
+        }
+        let field_of_play = &self.field_of_play_cache;
+        let swaps: Vec<(usize, usize)> = moves
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(move_index, (player_index, to))| {
+                let from = starts[*player_index];
+                let neighbours = [*to, (to.0, from.1), (from.0, to.1)];
+                neighbours
+                    .iter()
+                    .filter_map(|(x, y)| {
+                        let other_index = field_of_play
+                            .get_occupant(*x, *y)
+                            .and_then(|other_player| move_indices[other_player])
+                            .filter(|other_index| *other_index > move_index)?;
+                        let (other_player, other_to) = moves[other_index];
+                        is_swap(from, *to, starts[other_player], other_to).then_some((move_index, other_index))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        for (move_index, other_index) in swaps {
+            if allowed[move_index] && allowed[other_index] {
+                self.settle_conflict(Conflict::Swap, &[move_index, other_index], moves, &mut allowed);
+            }
+        }
+
+        allowed
+    }
+
+    // Lets at most one of the `contenders`, indices into `moves`, go ahead and stops the rest.
+    fn settle_conflict(
+        &mut self,
+        conflict: Conflict,
+        contenders: &[usize],
+        moves: &[(usize, (usize, usize))],
+        allowed: &mut [bool]
+    ) {
+        let winner = match self.collision_rule {
+            CollisionRule::RandomPriority => contenders.choose(&mut self.rng).copied(),
+            CollisionRule::Bounce => None,
+            CollisionRule::RiskTolerance => contenders.iter().copied().min_by(|a, b| {
+                let (a_player, b_player) = (&self.players[moves[*a].0], &self.players[moves[*b].0]);
+                b_player
+                    .get_risk_tolerance()
+                    .total_cmp(&a_player.get_risk_tolerance())
+                    .then(a_player.get_index().cmp(&b_player.get_index()))
+            })
+        };
+        let losers: Vec<String> = contenders
+            .iter()
+            .filter(|contender| Some(**contender) != winner)
+            .map(|contender| {
+                allowed[*contender] = false;
+                self.players[moves[*contender].0].name.to_owned()
+            })
+            .collect();
+        debug!(
+            "{:?} conflict on turn {}, stopped: {:?}",
+            conflict, self.turn_num, losers
+        );
+        self.stats.record_conflict(conflict, &losers);
+    }
+
+    fn start_player_turn(&mut self, player_index: usize, turn_position: usize) {
+        self.reveal_it_location(player_index);
+        let player = &mut self.players[player_index];
+        player.expire_tag_back_cooldowns(self.turn_num);
+        self.stats.record_start_player_details(player, turn_position);
+    }
+
+    // Both ways of moving call this for every player at the start of their turn, in turn order, so
+    // they agree on what players know about where the it players are.
+    fn reveal_it_location(&mut self, player_index: usize) {
+        let player = &self.players[player_index];
+        // We only set the last known it location here, instead of also when a new player is tagged
+        // to simulate a non-zero reaction time from the other players with regards to knowing who
+        // is it. Only it players give away where they are.
+        if player.is_it {
+            let (it_x, it_y) = player.get_location();
+            self.field_of_play_cache.set_last_known_it_location(it_x, it_y);
+        }
+    }
+
+    // The index and location of every it player, for players to look for. Players without vision
+    // do not look, so this is empty for them.
+    fn it_locations(&self) -> Vec<(usize, (usize, usize))> {
+        if self.config.vision.is_none() {
+            return Vec::new();
+        }
+
+        self.field_of_play_cache
+            .it_player_indices()
+            .map(|index| (index, self.players[index].get_location()))
+            .collect()
+    }
+
+    fn look_for_it(&mut self, player_index: usize, it_locations: &[(usize, (usize, usize))]) {
+        let player = &mut self.players[player_index];
+        if player.look_for_it(&self.field_of_play_cache, it_locations) {
+            self.stats.record_it_sighting(&player.name);
+        }
+    }
+
+    // `via` is every position passed through between the old and new positions.
+    fn move_player(
+        &mut self,
+        player_index: usize,
+        (old_x, old_y): (usize, usize),
+        (new_x, new_y): (usize, usize),
+        via: Vec<(usize, usize)>
+    ) {
+        // Clear the old position first so a player that stays put is not removed from the field.
+        self.field_of_play_cache.set_occupant(old_x, old_y, None);
+        self.field_of_play_cache.set_occupant(new_x, new_y, Some(player_index));
+        let player = &mut self.players[player_index];
+        let record = move_to(&self.field_of_play_cache, player, (old_x, old_y), (new_x, new_y), &via);
+        self.stats.record_move(&player.name, record);
+        let event = move_event(self.turn_num, player_index, (old_x, old_y), (new_x, new_y), via);
+        emit(&mut self.event_sinks, event);
+    }
+
+    // `(x, y)` is where the tagger was when they tagged.
+    fn tag_player(&mut self, player_index: usize, (x, y): (usize, usize), tagged_index: usize) {
+        let turn_num = self.turn_num;
+        let player_name = self.players[player_index].name.to_owned();
+        let tagged_player = &mut self.players[tagged_index];
+        match self.game_mode {
+            GameMode::Classic | GameMode::Infection => {
+                tagged_player.become_it(player_index, turn_num, self.tag_back_cooldown);
+                self.field_of_play_cache.set_it(tagged_index, true);
+                self.stats
+                    .record_new_it_details(tagged_player.name.to_owned(), turn_num);
+                if self.game_mode == GameMode::Classic {
+                    // The tagger stopped being it when they took the tag action.
+                    self.field_of_play_cache.set_it(player_index, false);
+                }
+            }
+            GameMode::Freeze => {
+                tagged_player.is_frozen = true;
+                self.field_of_play_cache.set_frozen(tagged_index, true);
+                self.stats.record_frozen_details(&tagged_player.name, turn_num);
+            }
+        }
+        self.stats.record_tag_given(&player_name, &tagged_player.name, turn_num);
+        info!("{} has tagged {}", player_name, tagged_player.name);
+        let (tagged_x, tagged_y) = tagged_player.get_location();
+        self.stats.record_tag_location(tagged_x, tagged_y);
+        emit(
+            &mut self.event_sinks,
+            Event::Tagged {
+                turn: turn_num,
+                player: player_index,
+                x,
+                y,
+                tagged_player: tagged_index,
+                tagged_x,
+                tagged_y
+            }
+        );
+    }
+
+    // `(x, y)` is where the rescuer was when they rescued.
+    fn rescue_player(&mut self, player_index: usize, (x, y): (usize, usize), rescued_index: usize) {
+        let player_name = self.players[player_index].name.to_owned();
+        let rescued_player = &mut self.players[rescued_index];
+        rescued_player.is_frozen = false;
+        self.field_of_play_cache.set_frozen(rescued_index, false);
+        self.stats.record_rescue_details(&player_name);
+        info!("{} has rescued {}", player_name, rescued_player.name);
+        let (rescued_x, rescued_y) = rescued_player.get_location();
+        emit(
+            &mut self.event_sinks,
+            Event::Rescued {
+                turn: self.turn_num,
+                player: player_index,
+                x,
+                y,
+                rescued_player: rescued_index,
+                rescued_x,
+                rescued_y
+            }
+        );
+    }
+}
+
+// A free function rather than a method so it can be used while other fields of the simulation are
+// borrowed.
+fn emit(event_sinks: &mut [Box<dyn EventSink>], event: Event) {
+    event_sinks.iter_mut().for_each(|event_sink| event_sink.record(&event));
+}
+
+// Moves `player` from `from` to `to` through `via`, turning them to face the way they last stepped
+// and tiring them, and returns what they did. The field of play must already show them at `to`. A
+// free function so players can be moved in parallel.
+fn move_to(
+    field_of_play: &FieldOfPlay,
+    player: &mut Player,
+    from: (usize, usize),
+    to: (usize, usize),
+    via: &[(usize, usize)]
+) -> MoveRecord {
+    let last_step_from = via.last().copied().unwrap_or(from);
+    if let Some(direction) = Direction::from_offset(field_of_play.get_offset(last_step_from, to)) {
+        player.set_facing(direction);
+    }
+    player.set_location(to.0, to.1);
+    let distance = if to == from { 0 } else { via.len() + 1 };
+    let stamina_before = player.get_stamina();
+    player.tire(distance);
+
+    MoveRecord {
+        distance,
+        stamina_before,
+        stamina_after: player.get_stamina(),
+        collided: false
+    }
+}
+
+// Simultaneous moves only. Leaves a player stopped by a collision where they are, tiring them as if
+// they had made the move through `via` they tried, so losing a collision is not a chance to rest.
+fn stop(player: &mut Player, via: &[(usize, usize)]) -> MoveRecord {
+    let stamina_before = player.get_stamina();
+    player.tire(via.len() + 1);
+
+    MoveRecord {
+        distance: 0,
+        stamina_before,
+        stamina_after: player.get_stamina(),
+        collided: true
+    }
+}
+
+fn move_event(
+    turn: usize,
+    player: usize,
+    (from_x, from_y): (usize, usize),
+    (to_x, to_y): (usize, usize),
+    via: Vec<(usize, usize)>
+) -> Event {
+    if (to_x, to_y) == (from_x, from_y) {
+        Event::Stuck {
+            turn,
+            player,
+            x: from_x,
+            y: from_y
+        }
+    } else {
+        Event::Moved {
+            turn,
+            player,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            via
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::models::heatmap::HeatmapKind;
+#[cfg(test)]
+use crate::models::player::SPRINT_DISTANCE;
+
+// Sprints two positions along the row towards x = 2.
+#[cfg(test)]
+struct ToTheMiddleStrategy;
+
+#[cfg(test)]
+impl MovementStrategy for ToTheMiddleStrategy {
+    fn choose_actions(&self, _: &FieldOfPlay, player: &Player, _: &mut SimRng) -> Vec<Action> {
+        let (x, y) = player.get_location();
+        let towards_middle = |x: usize| if x < 2 { x + 1 } else { x - 1 };
+
+        vec![Action::new_path(&[
+            (towards_middle(x), y),
+            (towards_middle(towards_middle(x)), y)
+        ])]
+    }
+
+    fn name(&self) -> &str {
+        "to-the-middle"
+    }
+}
+
+#[cfg(test)]
+fn test_config(seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        num_players: 5,
+        x_axis_len: 8,
+        y_axis_len: 8,
+        num_turns: 50,
+        seed: Some(seed),
+        ..SimulationConfig::default()
+    }
+}
+
+#[test]
+fn simulation_new_rejects_overfull_field_test() {
+    let mut config = test_config(1);
+    config.num_players = 65;
+    assert!(Simulation::new(config.clone()).is_err());
+
+    config.num_players = FieldOfPlay::MAX_PLAYERS + 1;
+    assert!(Simulation::new(config).is_err());
+}
+
+#[test]
+fn simulation_step_and_run_until_test() {
+    let mut simulation = Simulation::new(test_config(1)).unwrap();
+    assert_eq!(simulation.players().len(), 5);
+    assert_eq!(simulation.seed(), 1);
+
+    assert!(simulation.step());
+    assert_eq!(simulation.turn_num(), 1);
+
+    simulation.run_until(|sim| sim.turn_num() == 10);
+    assert_eq!(simulation.turn_num(), 10);
+    assert!(!simulation.is_finished());
+
+    simulation.run_until(|_| false);
+    assert!(simulation.is_finished());
+    assert!(!simulation.step());
+    assert_eq!(simulation.turn_num(), 50);
+
+    assert_eq!(simulation.field_of_play().get_player_positions().len(), 5);
+}
+
+#[test]
+fn simulation_heatmaps_test() {
+    let mut simulation = Simulation::new(test_config(4)).unwrap();
+    simulation.run_until(|_| false);
+    let stats = simulation.stats();
+
+    // Every player is counted once per turn and there is always exactly one it player in classic.
+    assert_eq!(stats.heatmap(HeatmapKind::Occupancy).total(), 5 * 50);
+    assert_eq!(stats.heatmap(HeatmapKind::It).total(), 50);
+    assert_eq!(stats.heatmap(HeatmapKind::Tags).total(), stats.tag_count());
+}
+
+#[test]
+fn simulation_simultaneous_moves_test() {
+    for (game_mode, collision_rule) in [
+        (GameMode::Classic, CollisionRule::RandomPriority),
+        (GameMode::Freeze, CollisionRule::Bounce),
+        (GameMode::Infection, CollisionRule::RiskTolerance)
+    ]
+    .iter()
+    {
+        let config = SimulationConfig {
+            num_players: 12,
+            num_turns: 200,
+            game_mode: *game_mode,
+            move_resolution: MoveResolution::Simultaneous,
+            collision_rule: *collision_rule,
+            ..test_config(8)
+        };
+        let mut simulation = Simulation::new(config.clone()).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        simulation.add_event_sink(Box::new(sender));
+        simulation.run_until(|_| false);
+        let stats = simulation.stats();
+
+        // A crowded field is bound to have collisions, and every one stops at least one player.
+        assert!(stats.same_cell_conflicts() > 0);
+        let conflicts_lost: usize = stats.player_stats().values().map(|player| player.conflicts_lost).sum();
+        assert!(conflicts_lost >= stats.same_cell_conflicts() + stats.swap_conflicts());
+        // Nobody ever shares a position.
+        let mut positions: Vec<(usize, usize)> = simulation.players().iter().map(Player::get_location).collect();
+        positions.sort_unstable();
+        positions.dedup();
+        assert_eq!(positions.len(), 12);
+        // The event log still only holds moves that could have happened.
+        let mut replay = crate::replay::Replay::new(receiver.try_iter().collect()).unwrap();
+        while replay.step().unwrap() {}
+
+        // Players decide in parallel, yet the same seed plays the same game on any number of threads.
+        for threads in [1, 4].iter() {
+            let same_seed = rayon::ThreadPoolBuilder::new()
+                .num_threads(*threads)
+                .build()
+                .unwrap()
+                .install(|| Simulation::new(config.clone()).unwrap().run());
+            assert_eq!(&same_seed, simulation.stats());
+        }
+    }
+}
+
+#[test]
+fn simulation_sprinting_test() {
+    for move_resolution in [MoveResolution::Sequential, MoveResolution::Simultaneous].iter() {
+        let config = SimulationConfig {
+            num_players: 8,
+            num_turns: 100,
+            move_resolution: *move_resolution,
+            max_speed: 3,
+            max_stamina: 4,
+            random_fitness: true,
+            ..test_config(3)
+        };
+        let mut simulation = Simulation::new(config).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        simulation.add_event_sink(Box::new(sender));
+        simulation.run_until(|_| false);
+        let stats = simulation.stats();
+
+        let events: Vec<Event> = receiver.try_iter().collect();
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::Moved { via, .. } if !via.is_empty())));
+        let mut replay = crate::replay::Replay::new(events).unwrap();
+        while replay.step().unwrap() {}
+        // Players start with their stamina full and end with whatever they did not spend.
+        assert!(stats.stamina_spent() > 0);
+        for player in simulation.players() {
+            let player_stats = &stats.player_stats()[&player.name];
+            assert!((1..=3).contains(&player.get_max_speed()));
+            assert!(player.get_max_stamina() <= 4);
+            assert_eq!(
+                player.get_max_stamina() + player_stats.stamina_recovered,
+                player.get_stamina() + player_stats.stamina_spent
+            );
+        }
+    }
+
+    assert!(Simulation::new(SimulationConfig {
+        max_speed: 0,
+        ..test_config(3)
+    })
+    .is_err());
+}
+
+#[test]
+fn simulation_stopped_sprinters_still_tire_test() {
+    let config = SimulationConfig {
+        num_players: 2,
+        num_turns: 10,
+        move_resolution: MoveResolution::Simultaneous,
+        collision_rule: CollisionRule::Bounce,
+        max_speed: 2,
+        arena: Some("I...S".parse().unwrap()),
+        ..test_config(1)
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+    simulation.set_runner_strategy(Box::new(ToTheMiddleStrategy));
+    simulation.set_chaser_strategy(Box::new(ToTheMiddleStrategy));
+    simulation.step();
+
+    // Both players sprint for the middle, so both are stopped. They stay where they are, but pay for
+    // the sprint they tried and are not counted as stuck.
+    assert_eq!(simulation.stats().same_cell_conflicts(), 1);
+    for (player, start) in simulation.players().iter().zip([(0, 0), (4, 0)].iter()) {
+        let player_stats = &simulation.stats().player_stats()[&player.name];
+        assert_eq!(player.get_location(), *start);
+        assert_eq!(player.get_stamina(), player.get_max_stamina() - 1);
+        assert_eq!(player_stats.stamina_spent, 1);
+        assert_eq!(player_stats.turns_stuck, 0);
+        assert_eq!(player_stats.conflicts_lost, 1);
+    }
+}
+
+#[test]
+fn simulation_last_known_it_location_test() {
+    let mut after_first_turn = Vec::new();
+    for move_resolution in [MoveResolution::Sequential, MoveResolution::Simultaneous].iter() {
+        let mut simulation = Simulation::new(SimulationConfig {
+            num_players: 8,
+            num_it_players: 2,
+            x_axis_len: 40,
+            y_axis_len: 40,
+            move_resolution: *move_resolution,
+            ..test_config(5)
+        })
+        .unwrap();
+        loop {
+            let starts: Vec<((usize, usize), bool)> = simulation
+                .players()
+                .iter()
+                .map(|player| (player.get_location(), player.is_it))
+                .collect();
+            if !simulation.step() {
+                break;
+            }
+            // Only ever where a player who was it during the turn started it.
+            let it_starts: Vec<(usize, usize)> = starts
+                .iter()
+                .zip(simulation.players())
+                .filter(|((_, was_it), player)| *was_it || player.is_it)
+                .map(|((start, _), _)| *start)
+                .collect();
+            let last_known = simulation.field_of_play().get_last_known_it_location().unwrap();
+            assert!(it_starts.contains(&last_known), "turn {}", simulation.turn_num());
+            if simulation.turn_num() == 1 {
+                assert_eq!(simulation.stats().tag_count(), 0);
+                after_first_turn.push(last_known);
+            }
+        }
+    }
+    // Players start in the same places for the same seed and are too far apart to tag anyone on the
+    // first turn, so both agree on where the it players were seen last.
+    assert_eq!(after_first_turn[0], after_first_turn[1]);
+}
+
+#[test]
+fn simulation_distant_runners_do_not_sprint_test() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        num_players: 3,
+        x_axis_len: 60,
+        y_axis_len: 60,
+        num_turns: 100,
+        max_speed: 3,
+        ..test_config(1)
+    })
+    .unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    simulation.add_event_sink(Box::new(sender));
+    let mut distant_moves = 0;
+    loop {
+        let starts: Vec<((usize, usize), bool)> = simulation
+            .players()
+            .iter()
+            .map(|player| (player.get_location(), player.is_it))
+            .collect();
+        if !simulation.step() {
+            break;
+        }
+        // Anyone who was it at some point in the turn may have been seen.
+        let it_starts: Vec<(usize, usize)> = starts
+            .iter()
+            .zip(simulation.players())
+            .filter(|((_, was_it), player)| *was_it || player.is_it)
+            .map(|((start, _), _)| *start)
+            .collect();
+        for event in receiver.try_iter() {
+            if let Event::Moved { player, via, .. } = event {
+                let (start, was_it) = starts[player];
+                let it_is_near = it_starts.iter().any(|it_start| {
+                    let (x_distance, y_distance) = simulation.field_of_play().get_axis_distances(start, *it_start);
+                    x_distance.max(y_distance) <= SPRINT_DISTANCE
+                });
+                if !was_it && !it_is_near {
+                    assert!(via.is_empty(), "p{} sprinted on turn {}", player, simulation.turn_num());
+                    distant_moves += 1;
+                }
+            }
+        }
+    }
+    assert!(distant_moves > 100);
+}
+
+#[test]
+fn simulation_vision_test() {
+    for move_resolution in [MoveResolution::Sequential, MoveResolution::Simultaneous].iter() {
+        let config = SimulationConfig {
+            num_players: 8,
+            num_turns: 100,
+            move_resolution: *move_resolution,
+            vision: Some(Vision { range: 4, angle: 90 }),
+            ..test_config(4)
+        };
+        let mut simulation = Simulation::new(config.clone()).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        simulation.add_event_sink(Box::new(sender));
+        simulation.run_until(|_| false);
+        let stats = simulation.stats();
+        assert_eq!(stats, &Simulation::new(config).unwrap().run());
+        assert_eq!(stats.vision(), Some(Vision { range: 4, angle: 90 }));
+        assert!(stats
+            .player_stats()
+            .values()
+            .any(|player_stats| player_stats.it_sightings > 0));
+
+        // Every player who moved faces the way they last stepped.
+        let events: Vec<Event> = receiver.try_iter().collect();
+        for player in simulation.players() {
+            let last_step = events.iter().rev().find_map(|event| match event {
+                Event::Moved {
+                    player: index,
+                    from_x,
+                    from_y,
+                    to_x,
+                    to_y,
+                    via,
+                    ..
+                } if *index == player.get_index() => {
+                    Some((via.last().copied().unwrap_or((*from_x, *from_y)), (*to_x, *to_y)))
+                }
+                _ => None
+            });
+            if let Some((from, to)) = last_step {
+                let offset = simulation.field_of_play().get_offset(from, to);
+                assert_eq!(Direction::from_offset(offset), Some(player.get_facing()));
+            }
+        }
+    }
+
+    assert!(Simulation::new(SimulationConfig {
+        vision: Some(Vision { range: 4, angle: 0 }),
+        ..test_config(4)
+    })
+    .is_err());
+}
+
+#[test]
+fn simulation_turn_order_test() {
+    let fixed = Simulation::new(test_config(6)).unwrap().run();
+    // With a fixed order every player always acts in the position of their index.
+    for player_stats in fixed.player_stats().values() {
+        assert_eq!(player_stats.mean_turn_position(), Some(player_stats.index as f64));
+    }
+
+    for turn_order in [
+        TurnOrder::Shuffled,
+        TurnOrder::ItFirst,
+        TurnOrder::ItLast,
+        TurnOrder::Initiative
+    ]
+    .iter()
+    {
+        let config = SimulationConfig {
+            turn_order: *turn_order,
+            ..test_config(6)
+        };
+        let stats = Simulation::new(config).unwrap().run();
+        assert_eq!(stats.turn_order(), *turn_order);
+        assert_eq!(stats.tags_by_turn_position().iter().sum::<usize>(), stats.tag_count());
+        assert_eq!(stats.tags_by_turn_position().len(), 5);
+    }
+}
+
+#[test]
+fn simulation_same_seed_same_stats_test() {
+    let first = Simulation::new(test_config(9)).unwrap().run();
+    let second = Simulation::new(test_config(9)).unwrap().run();
+
+    assert_eq!(first.to_string(), second.to_string());
+}
+
+#[test]
+fn simulation_same_seed_on_any_number_of_threads_test() {
+    for move_resolution in [MoveResolution::Sequential, MoveResolution::Simultaneous].iter() {
+        let config = SimulationConfig {
+            num_players: 40,
+            x_axis_len: 20,
+            y_axis_len: 20,
+            num_turns: 100,
+            game_mode: GameMode::Freeze,
+            move_resolution: *move_resolution,
+            max_speed: 3,
+            vision: Some(Vision { range: 6, angle: 120 }),
+            ..test_config(11)
+        };
+        let play = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| {
+                    let mut simulation = Simulation::new(config.clone()).unwrap();
+                    let (sender, receiver) = std::sync::mpsc::channel();
+                    simulation.add_event_sink(Box::new(sender));
+                    let stats = simulation.run();
+                    let events: Vec<Event> = receiver.try_iter().collect();
+                    (stats, events)
+                })
+        };
+
+        let (stats, events) = play(1);
+        assert!(stats.tag_count() > 0);
+        for threads in [2, 3, 8].iter() {
+            let (same_stats, same_events) = play(*threads);
+            assert_eq!(same_stats, stats);
+            assert_eq!(same_events, events);
+        }
+    }
+}
+
+#[test]
+fn simulation_chaser_strategies_run_test() {
+    ChaserStrategy::NAMES.iter().for_each(|name| {
+        let chaser_strategy: ChaserStrategy = name.parse().unwrap();
+        let config = SimulationConfig {
+            chaser_strategy,
+            ..test_config(3)
+        };
+        let stats = Simulation::new(config).unwrap().run();
+        let expected = format!("chaser strategy: {},", chaser_strategy.build().name());
+
+        assert!(stats.to_string().contains(&expected));
+    });
+}
+
+#[test]
+fn simulation_freeze_tag_ends_when_everyone_is_frozen_test() {
+    // A crowded field and a chasing it player so everyone gets frozen well before the turn limit.
+    let config = SimulationConfig {
+        num_players: 4,
+        x_axis_len: 3,
+        y_axis_len: 3,
+        num_turns: 10_000,
+        seed: Some(4),
+        chaser_strategy: ChaserStrategy::Nearest,
+        game_mode: GameMode::Freeze,
+        ..SimulationConfig::default()
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+    simulation.run_until(|_| false);
+
+    assert!(simulation.is_finished());
+    assert!(simulation.turn_num() < 10_000);
+    assert!(simulation
+        .players()
+        .iter()
+        .filter(|player| !player.is_it)
+        .all(|player| player.is_frozen));
+    assert_eq!(simulation.players().iter().filter(|player| player.is_it).count(), 1);
+    let stats = simulation.stats();
+    assert_eq!(stats.all_frozen_turn(), Some(simulation.turn_num()));
+    assert!(stats.freeze_count() >= 3);
+    assert_eq!(stats.freeze_count(), 3 + stats.rescue_count());
+}
+
+#[test]
+fn simulation_infection_spreads_to_everyone_test() {
+    let config = SimulationConfig {
+        num_players: 6,
+        x_axis_len: 4,
+        y_axis_len: 4,
+        num_turns: 10_000,
+        seed: Some(2),
+        chaser_strategy: ChaserStrategy::Nearest,
+        game_mode: GameMode::Infection,
+        num_it_players: 2,
+        ..SimulationConfig::default()
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+    simulation.run_until(|_| false);
+
+    assert!(simulation.turn_num() < 10_000);
+    assert!(simulation.players().iter().all(|player| player.is_it));
+    let stats = simulation.stats();
+    assert_eq!(stats.all_infected_turn(), Some(simulation.turn_num()));
+    assert_eq!(stats.tag_count(), 4);
+    let curve = stats.it_players_per_turn();
+    assert_eq!(curve.len(), simulation.turn_num());
+    assert_eq!(*curve.last().unwrap(), 6);
+    assert!(curve.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn simulation_classic_with_multiple_it_players_test() {
+    let config = SimulationConfig {
+        num_it_players: 3,
+        tag_back_cooldown: Some(5),
+        ..test_config(6)
+    };
+    assert!(Simulation::new(SimulationConfig {
+        num_it_players: 6,
+        ..test_config(6)
+    })
+    .is_err());
+
+    let mut simulation = Simulation::new(config).unwrap();
+    simulation.run_until(|_| false);
+
+    // Every tag passes it on, so the number of it players never changes.
+    assert!(simulation.stats().it_players_per_turn().iter().all(|count| *count == 3));
+    let field_of_play = simulation.field_of_play();
+    simulation
+        .players()
+        .iter()
+        .for_each(|player| assert_eq!(field_of_play.is_it(player.get_index()), player.is_it));
+}
+
+#[test]
+fn simulation_with_obstacles_test() {
+    let config = SimulationConfig {
+        obstacle_density: 0.2,
+        chaser_strategy: ChaserStrategy::Pathfinding,
+        ..test_config(8)
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+
+    while simulation.step() {
+        let field_of_play = simulation.field_of_play();
+        simulation.players().iter().for_each(|player| {
+            let (x, y) = player.get_location();
+            assert!(field_of_play.is_passable(x, y));
+            assert_eq!(field_of_play.get_occupant(x, y), Some(player.get_index()));
+        });
+    }
+
+    assert!(Simulation::new(SimulationConfig {
+        obstacle_density: 1.0,
+        ..test_config(8)
+    })
+    .is_err());
+}
+
+#[test]
+fn simulation_with_arena_test() {
+    let arena = "######\n#S..I#\n#S#..#\n######".parse::<Arena>().unwrap();
+    let config = SimulationConfig {
+        num_players: 3,
+        arena: Some(arena),
+        ..test_config(3)
+    };
+    let mut simulation = Simulation::new(config.clone()).unwrap();
+    assert_eq!(simulation.field_of_play().y_len(), 4);
+    assert_eq!(simulation.field_of_play().x_len(), 6);
+    assert_eq!(simulation.players()[0].get_location(), (4, 1));
+    assert_eq!(simulation.players()[1].get_location().0, 1);
+    assert_eq!(simulation.players()[2].get_location().0, 1);
+
+    while simulation.step() {
+        let field_of_play = simulation.field_of_play();
+        simulation.players().iter().for_each(|player| {
+            let (x, y) = player.get_location();
+            assert!(field_of_play.is_passable(x, y));
+        });
+    }
+
+    assert!(Simulation::new(SimulationConfig {
+        num_players: 4,
+        ..config
+    })
+    .is_err());
+}
+
+#[test]
+fn simulation_on_torus_test() {
+    let config = SimulationConfig {
+        topology: Topology::Torus,
+        chaser_strategy: ChaserStrategy::Nearest,
+        ..test_config(5)
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+    assert_eq!(simulation.field_of_play().topology(), Topology::Torus);
+
+    while simulation.step() {
+        simulation.players().iter().for_each(|player| {
+            let (x, y) = player.get_location();
+            assert_eq!(simulation.field_of_play().get_occupant(x, y), Some(player.get_index()));
+        });
+    }
+
+    assert!(Simulation::new(SimulationConfig {
+        topology: Topology::WrapY,
+        num_players: 3,
+        y_axis_len: 2,
+        ..test_config(5)
+    })
+    .is_err());
+}
+
+#[test]
+fn simulation_event_log_test() {
+    let config = SimulationConfig {
+        chaser_strategy: ChaserStrategy::Nearest,
+        ..test_config(2)
+    };
+    let mut simulation = Simulation::new(config).unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    simulation.add_event_sink(Box::new(sender));
+    let stats = simulation.run();
+    let events: Vec<Event> = receiver.try_iter().collect();
+
+    match &events[0] {
+        Event::GameStarted {
+            x_len, y_len, players, ..
+        } => {
+            assert_eq!((*x_len, *y_len), (8, 8));
+            assert_eq!(players.len(), 5);
+            assert!(players[0].is_it);
+        }
+        event => panic!("expected the game to start, got {:?}", event)
+    }
+    assert_eq!(
+        events.last(),
+        Some(&Event::GameEnded {
+            turn: 50,
+            reason: GameEndReason::TurnLimit
+        })
+    );
+    let count = |is_match: fn(&Event) -> bool| events.iter().filter(|event| is_match(event)).count();
+    assert_eq!(count(|event| matches!(event, Event::TurnStarted { .. })), 50);
+    assert_eq!(count(|event| matches!(event, Event::Tagged { .. })), stats.tag_count());
+    assert_eq!(
+        count(|event| matches!(event, Event::Moved { .. } | Event::Stuck { .. })),
+        50 * 5
+    );
+    events.iter().for_each(|event| {
+        if let Event::Moved {
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            via,
+            ..
+        } = event
+        {
+            assert!(from_x.abs_diff(*to_x) <= 1 && from_y.abs_diff(*to_y) <= 1);
+            assert!(via.is_empty());
+        }
+    });
+}
@@ -0,0 +1,574 @@
+use crate::models::resolution::{CollisionRule, MoveResolution};
+use crate::models::rules::GameMode;
+use crate::models::stats::Stats;
+use crate::models::topology::Topology;
+use crate::models::turn_order::TurnOrder;
+use crate::simulation::SimulationConfig;
+use serde::Serialize;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// How statistics are written out.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StatsFormat {
+    /// Aligned columns for reading in a terminal.
+    #[default]
+    Table,
+    /// A single JSON object with the config, the totals, the correlations and a list of players.
+    Json,
+    /// One row per player. Every row repeats the config and the totals so the rows of many runs can
+    /// be concatenated and compared.
+    Csv
+}
+
+impl StatsFormat {
+    pub const NAMES: [&'static str; 3] = ["table", "json", "csv"];
+}
+
+impl FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "table" => Ok(StatsFormat::Table),
+            "json" => Ok(StatsFormat::Json),
+            "csv" => Ok(StatsFormat::Csv),
+            _ => Err(format!(
+                "unknown stats format: {}. Must be one of: {}",
+                name,
+                StatsFormat::NAMES.join(", ")
+            ))
+        }
+    }
+}
+
+/// The statistics of a game along with how it was set up, in a fixed shape that can be written in
+/// any [StatsFormat]. Fields are only ever added, after the existing fields of the same kind, so
+/// scripts reading the output by name keep working.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StatsReport {
+    pub config: ReportConfig,
+    pub totals: ReportTotals,
+    /// The correlation between risk tolerance and each per player metric, in the same order as the
+    /// columns of [ReportPlayer]. See [Stats::risk_tolerance_correlations].
+    pub risk_tolerance_correlations: Vec<ReportCorrelation>,
+    /// Sorted by player index.
+    pub players: Vec<ReportPlayer>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReportConfig {
+    pub seed: Option<u64>,
+    pub game_mode: GameMode,
+    pub chaser_strategy: String,
+    pub topology: Topology,
+    pub num_players: usize,
+    pub num_it_players: usize,
+    pub num_turns: usize,
+    pub x_len: usize,
+    pub y_len: usize,
+    pub obstacle_density: f64,
+    pub tag_back_cooldown: Option<usize>,
+    pub turn_order: TurnOrder,
+    pub move_resolution: MoveResolution,
+    pub collision_rule: CollisionRule,
+    pub max_speed: usize,
+    pub max_stamina: usize,
+    pub random_fitness: bool,
+    /// Both None when players can always see the it player.
+    pub vision_range: Option<usize>,
+    pub vision_angle: Option<u32>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReportTotals {
+    pub turns_played: usize,
+    pub tag_count: usize,
+    pub tags_per_100_turns: f64,
+    pub mean_turns_between_tags: Option<f64>,
+    pub freeze_count: usize,
+    pub rescue_count: usize,
+    pub all_frozen_turn: Option<usize>,
+    pub all_infected_turn: Option<usize>,
+    /// See [TagNetwork::reciprocity](crate::models::tag_network::TagNetwork::reciprocity).
+    pub tag_reciprocity: Option<f64>,
+    /// See [Stats::tags_by_turn_position]. Written as the counts separated by spaces in the CSV and
+    /// table.
+    pub tags_by_turn_position: Vec<usize>,
+    /// See [Stats::same_cell_conflicts].
+    pub same_cell_conflicts: usize,
+    /// See [Stats::swap_conflicts].
+    pub swap_conflicts: usize,
+    /// See [Stats::stamina_spent].
+    pub total_stamina_spent: usize
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReportCorrelation {
+    pub metric: String,
+    pub correlation: Option<f64>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReportPlayer {
+    pub index: usize,
+    pub name: String,
+    pub risk_tolerance: f64,
+    pub rounds_started_as_it: usize,
+    pub rounds_made_it: usize,
+    pub times_frozen: usize,
+    pub rescues: usize,
+    pub distance_moved: usize,
+    pub turns_stuck: usize,
+    pub longest_it_streak: usize,
+    pub mean_turns_to_tag: Option<f64>,
+    pub tags_given: usize,
+    pub tags_received: usize,
+    pub mean_distance_from_it: Option<f64>,
+    /// The number of different players this player tagged.
+    pub players_tagged: usize,
+    /// The number of different players who tagged this player.
+    pub tagged_by_players: usize,
+    pub mean_turn_position: Option<f64>,
+    pub conflicts_lost: usize,
+    /// The player's own max speed and max stamina, which are only different from the config's with
+    /// random fitness.
+    pub max_speed: usize,
+    pub max_stamina: usize,
+    pub stamina_spent: usize,
+    pub stamina_recovered: usize,
+    pub it_sightings: usize
+}
+
+impl StatsReport {
+    /// `config` should be the config of the simulation that produced `stats`, as returned by
+    /// [Simulation::config](crate::Simulation::config) so the seed is always set.
+    pub fn new(config: &SimulationConfig, stats: &Stats) -> Self {
+        let (x_len, y_len) = stats.field_size();
+        let degrees = stats.tag_network().degrees();
+        let mut players: Vec<ReportPlayer> = stats
+            .player_stats()
+            .iter()
+            .map(|(name, player_stats)| ReportPlayer {
+                index: player_stats.index,
+                name: name.to_owned(),
+                risk_tolerance: player_stats.risk_tolerance,
+                rounds_started_as_it: player_stats.rounds_started_as_it,
+                rounds_made_it: player_stats.rounds_made_it,
+                times_frozen: player_stats.times_frozen,
+                rescues: player_stats.rescues,
+                distance_moved: player_stats.distance_moved,
+                turns_stuck: player_stats.turns_stuck,
+                longest_it_streak: player_stats.longest_it_streak,
+                mean_turns_to_tag: player_stats.mean_turns_to_tag(),
+                tags_given: player_stats.tags_given,
+                tags_received: player_stats.tags_received,
+                mean_distance_from_it: player_stats.mean_distance_from_it(),
+                players_tagged: degrees[player_stats.index].0,
+                tagged_by_players: degrees[player_stats.index].1,
+                mean_turn_position: player_stats.mean_turn_position(),
+                conflicts_lost: player_stats.conflicts_lost,
+                max_speed: player_stats.max_speed,
+                max_stamina: player_stats.max_stamina,
+                stamina_spent: player_stats.stamina_spent,
+                stamina_recovered: player_stats.stamina_recovered,
+                it_sightings: player_stats.it_sightings
+            })
+            .collect();
+        players.sort_by_key(|player| player.index);
+
+        StatsReport {
+            config: ReportConfig {
+                seed: config.seed,
+                game_mode: stats.game_mode(),
+                chaser_strategy: stats.chaser_strategy().to_owned(),
+                topology: config.topology,
+                num_players: players.len(),
+                num_it_players: config.num_it_players,
+                num_turns: stats.number_of_turns(),
+                x_len,
+                y_len,
+                obstacle_density: config.obstacle_density,
+                tag_back_cooldown: config.tag_back_cooldown,
+                turn_order: stats.turn_order(),
+                move_resolution: stats.move_resolution(),
+                collision_rule: stats.collision_rule(),
+                max_speed: config.max_speed,
+                max_stamina: config.max_stamina,
+                random_fitness: config.random_fitness,
+                vision_range: stats.vision().map(|vision| vision.range),
+                vision_angle: stats.vision().map(|vision| vision.angle)
+            },
+            totals: ReportTotals {
+                turns_played: stats.turns_played(),
+                tag_count: stats.tag_count(),
+                tags_per_100_turns: stats.tags_per_turn() * 100.0,
+                mean_turns_between_tags: stats.mean_turns_between_tags(),
+                freeze_count: stats.freeze_count(),
+                rescue_count: stats.rescue_count(),
+                all_frozen_turn: stats.all_frozen_turn(),
+                all_infected_turn: stats.all_infected_turn(),
+                tag_reciprocity: stats.tag_network().reciprocity(),
+                tags_by_turn_position: stats.tags_by_turn_position().to_vec(),
+                same_cell_conflicts: stats.same_cell_conflicts(),
+                swap_conflicts: stats.swap_conflicts(),
+                total_stamina_spent: stats.stamina_spent()
+            },
+            risk_tolerance_correlations: stats
+                .risk_tolerance_correlations()
+                .into_iter()
+                .map(|(metric, correlation)| ReportCorrelation {
+                    metric: metric.to_owned(),
+                    correlation
+                })
+                .collect(),
+            players
+        }
+    }
+
+    pub fn render(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Table => self.to_table(),
+            StatsFormat::Json => self.to_json(),
+            StatsFormat::Csv => self.to_csv()
+        }
+    }
+
+    /// Pretty printed JSON, ending in a newline.
+    pub fn to_json(&self) -> String {
+        // Every field is a plain value, number or string, so serializing can not fail.
+        let mut json = serde_json::to_string_pretty(self).expect("stats are always serializable");
+        json.push('\n');
+
+        json
+    }
+
+    /// A header row then one row per player. Missing values are left empty. Player columns with the
+    /// same name as a config column, such as the player's own `max_speed`, are prefixed with
+    /// `player_` so every column has a different name.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let player_columns: Vec<String> = PLAYER_COLUMNS
+            .iter()
+            .map(|column| {
+                if CONFIG_COLUMNS.contains(column) {
+                    format!("player_{}", column)
+                } else {
+                    column.to_string()
+                }
+            })
+            .collect();
+        let header: Vec<&str> = CONFIG_COLUMNS
+            .iter()
+            .chain(TOTALS_COLUMNS.iter())
+            .copied()
+            .chain(player_columns.iter().map(String::as_str))
+            .collect();
+        csv.push_str(&header.join(","));
+        csv.push('\n');
+        let mut shared = self.config_values();
+        shared.extend(self.totals_values());
+        for player in &self.players {
+            let row: Vec<String> = shared
+                .iter()
+                .cloned()
+                .chain(player_values(player))
+                .map(|value| csv_field(&value))
+                .collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// The config and totals as name: value lines, then a table of players and a table of
+    /// correlations with every column padded to line up.
+    pub fn to_table(&self) -> String {
+        let mut table = String::new();
+        let summary: Vec<(&str, String)> = CONFIG_COLUMNS
+            .iter()
+            .copied()
+            .zip(self.config_values())
+            .chain(TOTALS_COLUMNS.iter().copied().zip(self.totals_values()))
+            .collect();
+        let name_width = summary.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, value) in &summary {
+            let value = if value.is_empty() { "n/a" } else { value };
+            // Writing to a String can not fail.
+            let _ = writeln!(table, "{:<width$}  {}", name, value, width = name_width);
+        }
+        table.push('\n');
+
+        let player_rows: Vec<Vec<String>> = self.players.iter().map(player_values).collect();
+        table.push_str(&aligned(&PLAYER_COLUMNS, &player_rows));
+        table.push('\n');
+
+        let correlation_rows: Vec<Vec<String>> = self
+            .risk_tolerance_correlations
+            .iter()
+            .map(|correlation| vec![correlation.metric.to_owned(), format_optional(correlation.correlation)])
+            .collect();
+        table.push_str(&aligned(
+            &["metric", "correlation_with_risk_tolerance"],
+            &correlation_rows
+        ));
+
+        table
+    }
+
+    pub(crate) fn config_values(&self) -> Vec<String> {
+        let config = &self.config;
+        vec![
+            config.seed.map_or_else(String::new, |seed| seed.to_string()),
+            config.game_mode.to_string(),
+            config.chaser_strategy.to_owned(),
+            config.topology.to_string(),
+            config.num_players.to_string(),
+            config.num_it_players.to_string(),
+            config.num_turns.to_string(),
+            config.x_len.to_string(),
+            config.y_len.to_string(),
+            config.obstacle_density.to_string(),
+            config
+                .tag_back_cooldown
+                .map_or_else(String::new, |cooldown| cooldown.to_string()),
+            config.turn_order.to_string(),
+            config.move_resolution.to_string(),
+            config.collision_rule.to_string(),
+            config.max_speed.to_string(),
+            config.max_stamina.to_string(),
+            config.random_fitness.to_string(),
+            config.vision_range.map_or_else(String::new, |range| range.to_string()),
+            config.vision_angle.map_or_else(String::new, |angle| angle.to_string()),
+        ]
+    }
+
+    pub(crate) fn totals_values(&self) -> Vec<String> {
+        let totals = &self.totals;
+        vec![
+            totals.turns_played.to_string(),
+            totals.tag_count.to_string(),
+            format!("{:.2}", totals.tags_per_100_turns),
+            format_optional(totals.mean_turns_between_tags),
+            totals.freeze_count.to_string(),
+            totals.rescue_count.to_string(),
+            totals.all_frozen_turn.map_or_else(String::new, |turn| turn.to_string()),
+            totals
+                .all_infected_turn
+                .map_or_else(String::new, |turn| turn.to_string()),
+            format_optional(totals.tag_reciprocity),
+            totals
+                .tags_by_turn_position
+                .iter()
+                .map(|tags| tags.to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+            totals.same_cell_conflicts.to_string(),
+            totals.swap_conflicts.to_string(),
+            totals.total_stamina_spent.to_string(),
+        ]
+    }
+}
+
+pub(crate) const CONFIG_COLUMNS: [&str; 19] = [
+    "seed",
+    "game_mode",
+    "chaser_strategy",
+    "topology",
+    "num_players",
+    "num_it_players",
+    "num_turns",
+    "x_len",
+    "y_len",
+    "obstacle_density",
+    "tag_back_cooldown",
+    "turn_order",
+    "move_resolution",
+    "collision_rule",
+    "max_speed",
+    "max_stamina",
+    "random_fitness",
+    "vision_range",
+    "vision_angle"
+];
+
+pub(crate) const TOTALS_COLUMNS: [&str; 13] = [
+    "turns_played",
+    "tag_count",
+    "tags_per_100_turns",
+    "mean_turns_between_tags",
+    "freeze_count",
+    "rescue_count",
+    "all_frozen_turn",
+    "all_infected_turn",
+    "tag_reciprocity",
+    "tags_by_turn_position",
+    "same_cell_conflicts",
+    "swap_conflicts",
+    "total_stamina_spent"
+];
+
+const PLAYER_COLUMNS: [&str; 23] = [
+    "index",
+    "name",
+    "risk_tolerance",
+    "rounds_started_as_it",
+    "rounds_made_it",
+    "times_frozen",
+    "rescues",
+    "distance_moved",
+    "turns_stuck",
+    "longest_it_streak",
+    "mean_turns_to_tag",
+    "tags_given",
+    "tags_received",
+    "mean_distance_from_it",
+    "players_tagged",
+    "tagged_by_players",
+    "mean_turn_position",
+    "conflicts_lost",
+    "max_speed",
+    "max_stamina",
+    "stamina_spent",
+    "stamina_recovered",
+    "it_sightings"
+];
+
+fn player_values(player: &ReportPlayer) -> Vec<String> {
+    vec![
+        player.index.to_string(),
+        player.name.to_owned(),
+        format!("{:.2}", player.risk_tolerance),
+        player.rounds_started_as_it.to_string(),
+        player.rounds_made_it.to_string(),
+        player.times_frozen.to_string(),
+        player.rescues.to_string(),
+        player.distance_moved.to_string(),
+        player.turns_stuck.to_string(),
+        player.longest_it_streak.to_string(),
+        format_optional(player.mean_turns_to_tag),
+        player.tags_given.to_string(),
+        player.tags_received.to_string(),
+        format_optional(player.mean_distance_from_it),
+        player.players_tagged.to_string(),
+        player.tagged_by_players.to_string(),
+        format_optional(player.mean_turn_position),
+        player.conflicts_lost.to_string(),
+        player.max_speed.to_string(),
+        player.max_stamina.to_string(),
+        player.stamina_spent.to_string(),
+        player.stamina_recovered.to_string(),
+        player.it_sightings.to_string(),
+    ]
+}
+
+// Missing values are empty, which the table writes as n/a.
+pub(crate) fn format_optional(value: Option<f64>) -> String {
+    value.map_or_else(String::new, |value| format!("{:.2}", value))
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+// Pads every column to its widest value. Numbers are right aligned and text is left aligned.
+fn aligned(columns: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .map(|row| row[index].len().max(3))
+                .fold(column.len(), usize::max)
+        })
+        .collect();
+    let mut table = String::new();
+    let header: Vec<String> = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, width)| format!("{:<width$}", column, width = width))
+        .collect();
+    table.push_str(header.join("  ").trim_end());
+    table.push('\n');
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| {
+                let value = if value.is_empty() { "n/a" } else { value };
+                if value.parse::<f64>().is_ok() || value == "n/a" {
+                    format!("{:>width$}", value, width = width)
+                } else {
+                    format!("{:<width$}", value, width = width)
+                }
+            })
+            .collect();
+        table.push_str(cells.join("  ").trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+#[cfg(test)]
+use crate::simulation::Simulation;
+
+#[test]
+fn stats_report_formats_test() {
+    let simulation_config = SimulationConfig {
+        num_players: 12,
+        x_axis_len: 10,
+        y_axis_len: 10,
+        num_turns: 40,
+        seed: Some(7),
+        ..SimulationConfig::default()
+    };
+    let mut simulation = Simulation::new(simulation_config).unwrap();
+    simulation.run_until(|_| false);
+    let report = StatsReport::new(simulation.config(), simulation.stats());
+
+    // Players are in index order, not name order where p10 would come before p2.
+    let indices: Vec<usize> = report.players.iter().map(|player| player.index).collect();
+    assert_eq!(indices, (0..12).collect::<Vec<usize>>());
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["config"]["seed"], 7);
+    assert_eq!(json["config"]["game_mode"], "classic");
+    assert_eq!(json["totals"]["turns_played"], 40);
+    assert_eq!(json["players"][10]["name"], "p10");
+    assert_eq!(json["players"][10]["max_speed"], 1);
+    assert_eq!(json["players"][10]["max_stamina"], 10);
+
+    let csv = report.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 13);
+    let header: Vec<&str> = lines[0].split(',').collect();
+    let column_count = header.len();
+    assert!(header
+        .iter()
+        .all(|column| header.iter().filter(|other| *other == column).count() == 1));
+    assert_eq!(column_count, 55);
+    assert!(header.contains(&"player_max_speed") && header.contains(&"player_max_stamina"));
+    assert!(lines.iter().all(|line| line.split(',').count() == column_count));
+    assert!(lines[1].starts_with(
+        "7,classic,random-avoidance,bounded,12,1,40,10,10,0,,fixed,sequential,random-priority,1,10,false,,,40,"
+    ));
+
+    let table = report.to_table();
+    assert!(table.contains("seed                     7\n"));
+    let player_lines: Vec<&str> = table.lines().skip_while(|line| !line.starts_with("index")).collect();
+    assert!(player_lines[0].contains(" max_speed ") && player_lines[0].contains(" max_stamina "));
+    assert!(player_lines[1..13]
+        .iter()
+        .all(|line| line.len() == player_lines[1].len()));
+
+    assert_eq!(
+        "yaml".parse::<StatsFormat>().unwrap_err(),
+        "unknown stats format: yaml. Must be one of: table, json, csv"
+    );
+}
@@ -0,0 +1,459 @@
+use crate::batch::run_batch_with_progress;
+use crate::models::pursuit::ChaserStrategy;
+use crate::models::resolution::{CollisionRule, MoveResolution};
+use crate::models::rules::GameMode;
+use crate::models::topology::Topology;
+use crate::models::turn_order::TurnOrder;
+use crate::models::vision::Vision;
+use crate::simulation::{Simulation, SimulationConfig};
+use crate::stats_report::{self, ReportTotals, StatsReport, CONFIG_COLUMNS, TOTALS_COLUMNS};
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::path::Path;
+
+/// The values to try for a whole number parameter of a sweep. In a spec this is either a list,
+/// `[10, 20, 40]`, or an inclusive range, `{"from": 10, "to": 50, "step": 10}`, where the step
+/// defaults to 1.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SweepValues {
+    List(Vec<usize>),
+    Range {
+        from: usize,
+        to: usize,
+        #[serde(default = "default_step")]
+        step: usize
+    }
+}
+
+fn default_step() -> usize {
+    1
+}
+
+impl SweepValues {
+    pub fn values(&self) -> Result<Vec<usize>, String> {
+        match self {
+            SweepValues::List(values) if values.is_empty() => Err("a list of values can not be empty".to_owned()),
+            SweepValues::List(values) => Ok(values.clone()),
+            SweepValues::Range { step: 0, .. } => Err("the step of a range must be at least 1".to_owned()),
+            SweepValues::Range { from, to, .. } if from > to => {
+                Err(format!("the range from {} to {} is empty", from, to))
+            }
+            SweepValues::Range { from, to, step } => Ok((*from..=*to).step_by(*step).collect())
+        }
+    }
+}
+
+/// Which games a sweep plays. A game is played for every combination of the parameters, called a
+/// cell, and every cell is played `repetitions` times. Parameters missing from the spec take the
+/// value of [SimulationConfig::default]. Giving a `vision_range` turns on [Vision], with an angle of
+/// [Vision::DEFAULT_ANGLE] unless `vision_angle` is also given.
+///
+/// Repetition `n` of every cell is played with the seed `base_seed + n`, so cells are compared over
+/// the same seeds and a sweep always produces the same results.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SweepSpec {
+    pub num_players: Option<SweepValues>,
+    pub x_len: Option<SweepValues>,
+    pub y_len: Option<SweepValues>,
+    pub num_turns: Option<SweepValues>,
+    pub num_it_players: Option<SweepValues>,
+    pub max_speed: Option<SweepValues>,
+    pub max_stamina: Option<SweepValues>,
+    pub vision_range: Option<SweepValues>,
+    pub vision_angle: Option<SweepValues>,
+    #[serde(default)]
+    pub obstacle_density: Vec<f64>,
+    #[serde(default)]
+    pub game_mode: Vec<GameMode>,
+    #[serde(default)]
+    pub chaser_strategy: Vec<ChaserStrategy>,
+    #[serde(default)]
+    pub topology: Vec<Topology>,
+    #[serde(default)]
+    pub turn_order: Vec<TurnOrder>,
+    #[serde(default)]
+    pub move_resolution: Vec<MoveResolution>,
+    #[serde(default)]
+    pub collision_rule: Vec<CollisionRule>,
+    #[serde(default)]
+    pub random_fitness: Vec<bool>,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: usize,
+    #[serde(default)]
+    pub base_seed: u64
+}
+
+fn default_repetitions() -> usize {
+    1
+}
+
+impl SweepSpec {
+    /// Reads a spec written as a JSON object, such as
+    /// `{"num_players": [5, 10], "x_len": {"from": 20, "to": 60, "step": 20}, "repetitions": 10}`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|err| format!("invalid sweep spec: {}", err))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read sweep spec {}: {}", path.display(), err))?;
+        SweepSpec::from_json(&json)
+    }
+
+    /// The config of every cell, without a seed. The last parameter changes fastest.
+    pub fn cells(&self) -> Result<Vec<SimulationConfig>, String> {
+        if self.repetitions == 0 {
+            return Err("a sweep needs at least one repetition".to_owned());
+        }
+        let default = SimulationConfig::default();
+        let values = |name: &str, values: &Option<SweepValues>, default: usize| {
+            values.as_ref().map_or(Ok(vec![default]), |values| {
+                values.values().map_err(|err| format!("{}: {}", name, err))
+            })
+        };
+        let num_players = values("num_players", &self.num_players, default.num_players)?;
+        let x_lens = values("x_len", &self.x_len, default.x_axis_len)?;
+        let y_lens = values("y_len", &self.y_len, default.y_axis_len)?;
+        let num_turns = values("num_turns", &self.num_turns, default.num_turns)?;
+        let num_it_players = values("num_it_players", &self.num_it_players, default.num_it_players)?;
+        let max_speeds = values("max_speed", &self.max_speed, default.max_speed)?;
+        let max_staminas = values("max_stamina", &self.max_stamina, default.max_stamina)?;
+        let visions = self.visions()?;
+
+        // Each parameter is added in turn, so the last one changes fastest.
+        let cells = vec![default.clone()];
+        let cells = expand(
+            cells,
+            &or_default(&self.game_mode, default.game_mode),
+            |config, game_mode| config.game_mode = game_mode
+        );
+        let cells = expand(
+            cells,
+            &or_default(&self.chaser_strategy, default.chaser_strategy),
+            |config, chaser_strategy| config.chaser_strategy = chaser_strategy
+        );
+        let cells = expand(
+            cells,
+            &or_default(&self.topology, default.topology),
+            |config, topology| config.topology = topology
+        );
+        let cells = expand(
+            cells,
+            &or_default(&self.turn_order, default.turn_order),
+            |config, turn_order| config.turn_order = turn_order
+        );
+        let cells = expand(
+            cells,
+            &or_default(&self.move_resolution, default.move_resolution),
+            |config, move_resolution| config.move_resolution = move_resolution
+        );
+        let cells = expand(
+            cells,
+            &or_default(&self.collision_rule, default.collision_rule),
+            |config, collision_rule| config.collision_rule = collision_rule
+        );
+        let cells = expand(
+            cells,
+            &or_default(&self.random_fitness, default.random_fitness),
+            |config, random_fitness| config.random_fitness = random_fitness
+        );
+        let cells = expand(cells, &num_players, |config, num_players| {
+            config.num_players = num_players
+        });
+        let cells = expand(cells, &num_it_players, |config, num_it_players| {
+            config.num_it_players = num_it_players
+        });
+        let cells = expand(cells, &max_speeds, |config, max_speed| config.max_speed = max_speed);
+        let cells = expand(cells, &max_staminas, |config, max_stamina| {
+            config.max_stamina = max_stamina
+        });
+        let cells = expand(cells, &visions, |config, vision| config.vision = vision);
+        let cells = expand(cells, &x_lens, |config, x_len| config.x_axis_len = x_len);
+        let cells = expand(cells, &y_lens, |config, y_len| config.y_axis_len = y_len);
+        let cells = expand(
+            cells,
+            &or_default(&self.obstacle_density, default.obstacle_density),
+            |config, obstacle_density| config.obstacle_density = obstacle_density
+        );
+        let cells = expand(cells, &num_turns, |config, num_turns| config.num_turns = num_turns);
+
+        Ok(cells)
+    }
+
+    // Every combination of the vision ranges and angles, or no vision at all without a range.
+    fn visions(&self) -> Result<Vec<Option<Vision>>, String> {
+        let ranges = match &self.vision_range {
+            Some(ranges) => ranges.values().map_err(|err| format!("vision_range: {}", err))?,
+            None if self.vision_angle.is_some() => return Err("vision_angle: needs a vision_range".to_owned()),
+            None => return Ok(vec![None])
+        };
+        let angles = match &self.vision_angle {
+            Some(angles) => angles.values().map_err(|err| format!("vision_angle: {}", err))?,
+            None => vec![Vision::DEFAULT_ANGLE as usize]
+        };
+
+        Ok(ranges
+            .iter()
+            .flat_map(|range| {
+                angles.iter().map(move |angle| {
+                    Some(Vision {
+                        range: *range,
+                        angle: u32::try_from(*angle).unwrap_or(u32::MAX)
+                    })
+                })
+            })
+            .collect())
+    }
+}
+
+// Every config in `cells` with every one of `values`, set by `set`.
+fn expand<T, F>(cells: Vec<SimulationConfig>, values: &[T], set: F) -> Vec<SimulationConfig>
+where
+    T: Copy,
+    F: Fn(&mut SimulationConfig, T)
+{
+    let mut expanded = Vec::with_capacity(cells.len() * values.len());
+    for cell in &cells {
+        for value in values {
+            let mut config = cell.clone();
+            set(&mut config, *value);
+            expanded.push(config);
+        }
+    }
+
+    expanded
+}
+
+fn or_default<T: Copy>(values: &[T], default: T) -> Vec<T> {
+    if values.is_empty() {
+        vec![default]
+    } else {
+        values.to_vec()
+    }
+}
+
+/// One game played by a sweep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepRun {
+    /// The index of the cell in [SweepSpec::cells].
+    pub cell: usize,
+    pub repetition: usize,
+    pub report: StatsReport
+}
+
+/// Plays every game of `spec` without displaying anything, across the threads of the current Rayon
+/// thread pool with [run_batch](crate::batch::run_batch). The runs are returned cell by cell and are
+/// the same whatever the number of threads. `progress` is called after each game with the number of
+/// games played so far and the total.
+///
+/// Every cell is checked before any game is played, so a cell that can not be played, such as one
+/// with more players than fit on its field, fails the sweep straight away.
+pub fn run_sweep<F>(spec: &SweepSpec, progress: F) -> Result<Vec<SweepRun>, String>
+where
+    F: Fn(usize, usize) + Sync
+{
+    let cells = spec.cells()?;
+    for config in &cells {
+        Simulation::new(config.clone()).map_err(|err| format!("{}: {}", describe(config), err))?;
+    }
+
+    let configs: Vec<SimulationConfig> = cells
+        .iter()
+        .flat_map(|config| {
+            (0..spec.repetitions).map(move |repetition| SimulationConfig {
+                seed: Some(spec.base_seed.wrapping_add(repetition as u64)),
+                ..config.clone()
+            })
+        })
+        .collect();
+    let results = run_batch_with_progress(&configs, progress);
+
+    configs
+        .iter()
+        .zip(results)
+        .enumerate()
+        .map(|(index, (config, stats))| {
+            let stats = stats.map_err(|err| format!("{}: {}", describe(config), err))?;
+            Ok(SweepRun {
+                cell: index / spec.repetitions,
+                repetition: index % spec.repetitions,
+                report: StatsReport::new(config, &stats)
+            })
+        })
+        .collect()
+}
+
+fn describe(config: &SimulationConfig) -> String {
+    format!(
+        "{} players ({} it) on {} x {} for {} turns",
+        config.num_players, config.num_it_players, config.x_axis_len, config.y_axis_len, config.num_turns
+    )
+}
+
+// The statistics written for each cell, in the order they are written.
+const SUMMARIES: [&str; 4] = ["mean", "std_dev", "min", "max"];
+
+/// The results of a sweep as a single CSV. There is a row for every game, with `row` set to `run`,
+/// and after the games of each cell a row for each of the mean, sample standard deviation, minimum
+/// and maximum of every total over the games of the cell, with `row` set to the name of the
+/// statistic. Columns are the config and totals columns of [StatsReport::to_csv]. Totals missing
+/// from a game are left out of the statistics, and summary rows leave the seed and repetition
+/// empty.
+pub fn sweep_to_csv(runs: &[SweepRun]) -> String {
+    let mut csv = String::new();
+    let header: Vec<&str> = ["row", "cell", "repetition"]
+        .iter()
+        .chain(CONFIG_COLUMNS.iter())
+        .chain(TOTALS_COLUMNS.iter())
+        .copied()
+        .collect();
+    csv.push_str(&header.join(","));
+    csv.push('\n');
+
+    let mut push_row = |row: Vec<String>| {
+        let row: Vec<String> = row.iter().map(|value| stats_report::csv_field(value)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    };
+    for cell_runs in runs.chunk_by(|run, next| run.cell == next.cell) {
+        for run in cell_runs {
+            let mut row = vec!["run".to_owned(), run.cell.to_string(), run.repetition.to_string()];
+            row.extend(run.report.config_values());
+            row.extend(run.report.totals_values());
+            push_row(row);
+        }
+
+        let cell = cell_runs[0].cell;
+        let mut config = cell_runs[0].report.config_values();
+        // The seed is the first config column and differs between the games of a cell.
+        config[0] = String::new();
+        let totals: Vec<Vec<f64>> = (0..TOTALS_COLUMNS.len())
+            .map(|column| {
+                cell_runs
+                    .iter()
+                    .filter_map(|run| total_values(&run.report.totals)[column])
+                    .collect()
+            })
+            .collect();
+        for summary in SUMMARIES.iter() {
+            let mut row = vec![summary.to_string(), cell.to_string(), String::new()];
+            row.extend(config.iter().cloned());
+            row.extend(
+                totals
+                    .iter()
+                    .map(|values| stats_report::format_optional(summarize(summary, values)))
+            );
+            push_row(row);
+        }
+    }
+
+    csv
+}
+
+// The totals as numbers, in the order of TOTALS_COLUMNS. Totals that are not a single number are
+// None.
+fn total_values(totals: &ReportTotals) -> [Option<f64>; TOTALS_COLUMNS.len()] {
+    [
+        Some(totals.turns_played as f64),
+        Some(totals.tag_count as f64),
+        Some(totals.tags_per_100_turns),
+        totals.mean_turns_between_tags,
+        Some(totals.freeze_count as f64),
+        Some(totals.rescue_count as f64),
+        totals.all_frozen_turn.map(|turn| turn as f64),
+        totals.all_infected_turn.map(|turn| turn as f64),
+        totals.tag_reciprocity,
+        None,
+        Some(totals.same_cell_conflicts as f64),
+        Some(totals.swap_conflicts as f64),
+        Some(totals.total_stamina_spent as f64)
+    ]
+}
+
+fn summarize(summary: &str, values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    match summary {
+        "mean" => Some(mean),
+        "std_dev" if values.len() < 2 => None,
+        "std_dev" => {
+            let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+            Some(variance.sqrt())
+        }
+        "min" => values.iter().copied().reduce(f64::min),
+        _ => values.iter().copied().reduce(f64::max)
+    }
+}
+
+#[test]
+fn sweep_spec_test() {
+    let spec = SweepSpec::from_json(
+        r#"{"num_players": [3, 4], "x_len": {"from": 5, "to": 9, "step": 2}, "game_mode": ["classic", "freeze"],
+            "repetitions": 2, "base_seed": 7}"#
+    )
+    .unwrap();
+    let cells = spec.cells().unwrap();
+    assert_eq!(cells.len(), 2 * 3 * 2);
+    assert_eq!(cells[0].x_axis_len, 5);
+    assert_eq!(cells[2].x_axis_len, 9);
+    assert_eq!(cells[3].num_players, 4);
+    assert_eq!(cells[6].game_mode, GameMode::Freeze);
+    assert_eq!(cells[0].y_axis_len, SimulationConfig::default().y_axis_len);
+
+    assert!(SweepSpec::from_json(r#"{"players": [3]}"#).is_err());
+    assert_eq!(
+        SweepSpec::from_json(r#"{"x_len": {"from": 9, "to": 5}}"#)
+            .unwrap()
+            .cells()
+            .unwrap_err(),
+        "x_len: the range from 9 to 5 is empty"
+    );
+
+    let spec = SweepSpec::from_json(r#"{"vision_range": [3, 6], "vision_angle": [90, 360]}"#).unwrap();
+    let cells = spec.cells().unwrap();
+    assert_eq!(cells.len(), 4);
+    assert_eq!(cells[1].vision, Some(Vision { range: 3, angle: 360 }));
+    assert_eq!(
+        SweepSpec::from_json(r#"{"vision_angle": [90]}"#)
+            .unwrap()
+            .cells()
+            .unwrap_err(),
+        "vision_angle: needs a vision_range"
+    );
+}
+
+#[test]
+fn run_sweep_test() {
+    let spec = SweepSpec::from_json(
+        r#"{"num_players": [3, 4], "x_len": [6], "y_len": [5], "num_turns": [20], "repetitions": 3,
+            "base_seed": 11}"#
+    )
+    .unwrap();
+    let played = std::sync::Mutex::new(Vec::new());
+    let runs = run_sweep(&spec, |done, total| played.lock().unwrap().push((done, total))).unwrap();
+    assert_eq!(runs.len(), 6);
+    assert_eq!(played.lock().unwrap().iter().max(), Some(&(6, 6)));
+    assert_eq!(runs[4].report.config.seed, Some(12));
+    assert_eq!(runs[4].report.config.num_players, 4);
+
+    let csv = sweep_to_csv(&runs);
+    let lines: Vec<&str> = csv.lines().collect();
+    // A header, then 3 runs and 4 summaries for each of the 2 cells.
+    assert_eq!(lines.len(), 1 + 2 * (3 + 4));
+    assert!(lines[0].starts_with("row,cell,repetition,seed,game_mode,"));
+    assert!(lines[1].starts_with("run,0,0,11,classic,"));
+    assert!(lines[4].starts_with("mean,0,,,classic,"));
+    // Every game lasts the full 20 turns.
+    let turns_played = 3 + CONFIG_COLUMNS.len();
+    assert_eq!(lines[4].split(',').nth(turns_played), Some("20.00"));
+    assert_eq!(lines[5].split(',').nth(turns_played), Some("0.00"));
+
+    let spec = SweepSpec::from_json(r#"{"num_players": [40], "x_len": [3], "y_len": [3]}"#).unwrap();
+    assert!(run_sweep(&spec, |_, _| ())
+        .unwrap_err()
+        .starts_with("40 players (1 it) on 3 x 3 for 1000 turns: "));
+}